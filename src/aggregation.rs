@@ -0,0 +1,391 @@
+use crate::traits::{DecryptionKey, EncryptionKey, HomomorphicKey};
+use crypto_bigint::{Limb, Uint};
+use rand_core::CryptoRng;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::marker::PhantomData;
+
+// decoded slot sums are handled as i128
+const MAX_SLOT_BITS: u32 = 120;
+const MAX_VALUE_BITS: u32 = 62;
+
+// values are encoded as round(x * 2^fractional_bits) + 2^(value_bits - 1) so that every encoding is a non negative
+// integer below 2^value_bits, slots are slot_bits wide and the headroom above value_bits absorbs the carries of the sum
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AggregationParams {
+    pub dimension: usize,
+    pub fractional_bits: u32,
+    pub value_bits: u32,
+    pub slot_bits: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AggregationError {
+    InvalidParams,
+    ValueOutOfRange(usize),
+    DimensionMismatch,
+    DuplicateClient(u64),
+    TooManySummands,
+    NoSummands,
+    InvalidCiphertext,
+}
+
+impl fmt::Display for AggregationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AggregationError::InvalidParams => write!(f, "aggregation parameters do not fit the key"),
+            AggregationError::ValueOutOfRange(i) => write!(f, "value {i} does not fit the fixed point encoding"),
+            AggregationError::DimensionMismatch => write!(f, "vector length does not match the parameters"),
+            AggregationError::DuplicateClient(id) => write!(f, "update of client {id} is already included"),
+            AggregationError::TooManySummands => write!(f, "another summand would overflow the slots"),
+            AggregationError::NoSummands => write!(f, "aggregate does not include any update"),
+            AggregationError::InvalidCiphertext => write!(f, "ciphertext is not valid for the key"),
+        }
+    }
+}
+
+impl std::error::Error for AggregationError {}
+
+// works with any additively homomorphic key, e.g. Paillier or Joye-Libert, the packing adapts to the plaintext space
+#[derive(Debug, Clone)]
+pub struct SecureAggregation<K, const S: usize> {
+    key: K,
+    params: AggregationParams,
+    slots: usize,
+    plaintext: PhantomData<Uint<S>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientUpdate<C> {
+    pub client_id: u64,
+    pub ciphertexts: Vec<C>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Aggregate<C> {
+    // empty until the first update is added
+    ciphertexts: Vec<C>,
+    included: BTreeSet<u64>,
+}
+
+impl<C> Aggregate<C> {
+    pub fn ciphertexts(&self) -> &[C] {
+        &self.ciphertexts
+    }
+
+    pub fn included(&self) -> &BTreeSet<u64> {
+        &self.included
+    }
+
+    // clients that were expected but whose updates never made it into the aggregate
+    pub fn dropped_out(&self, expected: &[u64]) -> Vec<u64> {
+        expected
+            .iter()
+            .filter(|id| !self.included.contains(id))
+            .copied()
+            .collect()
+    }
+}
+
+impl<K, const S: usize> SecureAggregation<K, S>
+where
+    K: EncryptionKey<Uint<S>> + HomomorphicKey<Uint<S>>,
+    K::Ciphertext: Clone,
+{
+    pub fn new(key: K, params: AggregationParams) -> Result<Self, AggregationError> {
+        // packed plaintexts have to be valid for the key, only the bits of its widest valid all ones value are usable
+        let plaintext_bits = (1..=Uint::<S>::BITS)
+            .rev()
+            .find(|&bits| bool::from(key.plaintext_is_valid(&Uint::MAX.shr_vartime(Uint::<S>::BITS - bits))))
+            .unwrap_or(0);
+        if params.dimension == 0
+            || params.value_bits < 2
+            || params.value_bits > MAX_VALUE_BITS
+            || params.fractional_bits >= params.value_bits
+            || params.slot_bits <= params.value_bits
+            || params.slot_bits > MAX_SLOT_BITS
+            || params.slot_bits > plaintext_bits
+        {
+            return Err(AggregationError::InvalidParams);
+        }
+
+        let slots = (plaintext_bits / params.slot_bits) as usize;
+        Ok(SecureAggregation {
+            key,
+            params,
+            slots,
+            plaintext: PhantomData,
+        })
+    }
+
+    pub fn params(&self) -> &AggregationParams {
+        &self.params
+    }
+
+    pub fn slots_per_ciphertext(&self) -> usize {
+        self.slots
+    }
+
+    pub fn ciphertexts_per_update(&self) -> usize {
+        self.params.dimension.div_ceil(self.slots)
+    }
+
+    // a slot holds the sum of up to 2^(slot_bits - value_bits) encodings before it carries into its neighbour
+    pub fn max_summands(&self) -> u64 {
+        1u64.checked_shl(self.params.slot_bits - self.params.value_bits)
+            .unwrap_or(u64::MAX)
+    }
+
+    pub fn encrypt_update<R: CryptoRng + ?Sized>(
+        &self,
+        client_id: u64,
+        values: &[f64],
+        rng: &mut R,
+    ) -> Result<ClientUpdate<K::Ciphertext>, AggregationError> {
+        if values.len() != self.params.dimension {
+            return Err(AggregationError::DimensionMismatch);
+        }
+
+        let encoded = values
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| self.encode(x).ok_or(AggregationError::ValueOutOfRange(i)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ciphertexts = encoded
+            .chunks(self.slots)
+            .map(|chunk| {
+                let m = chunk.iter().enumerate().fold(Uint::<S>::ZERO, |acc, (j, &e)| {
+                    acc.bitor(&Uint::from_u64(e).shl_vartime(j as u32 * self.params.slot_bits))
+                });
+                self.key.encrypt(&m, rng).0
+            })
+            .collect();
+
+        Ok(ClientUpdate { client_id, ciphertexts })
+    }
+
+    pub fn empty_aggregate(&self) -> Aggregate<K::Ciphertext> {
+        Aggregate {
+            ciphertexts: Vec::new(),
+            included: BTreeSet::new(),
+        }
+    }
+
+    pub fn add(
+        &self,
+        aggregate: &mut Aggregate<K::Ciphertext>,
+        update: &ClientUpdate<K::Ciphertext>,
+    ) -> Result<(), AggregationError> {
+        if update.ciphertexts.len() != self.ciphertexts_per_update() {
+            return Err(AggregationError::DimensionMismatch);
+        }
+        if aggregate.included.contains(&update.client_id) {
+            return Err(AggregationError::DuplicateClient(update.client_id));
+        }
+        if aggregate.included.len() as u64 >= self.max_summands() {
+            return Err(AggregationError::TooManySummands);
+        }
+        if !update
+            .ciphertexts
+            .iter()
+            .all(|c| self.key.ciphertext_is_valid(c).into())
+        {
+            return Err(AggregationError::InvalidCiphertext);
+        }
+
+        if aggregate.ciphertexts.is_empty() {
+            aggregate.ciphertexts = update.ciphertexts.clone();
+        } else {
+            for (acc, c) in aggregate.ciphertexts.iter_mut().zip(&update.ciphertexts) {
+                *acc = self.key.ciphertext_add(acc, c);
+            }
+        }
+        aggregate.included.insert(update.client_id);
+        Ok(())
+    }
+
+    // plaintexts of the aggregate ciphertexts, obtained with any decryption, are unpacked into coordinate averages
+    pub fn decode_average(
+        &self,
+        aggregate: &Aggregate<K::Ciphertext>,
+        plaintexts: &[Uint<S>],
+    ) -> Result<Vec<f64>, AggregationError> {
+        if aggregate.included.is_empty() {
+            return Err(AggregationError::NoSummands);
+        }
+        if plaintexts.len() != aggregate.ciphertexts.len() {
+            return Err(AggregationError::DimensionMismatch);
+        }
+
+        let count = aggregate.included.len() as i128;
+        let offset = 1i128 << (self.params.value_bits - 1);
+        let scale = (1u64 << self.params.fractional_bits) as f64;
+        let mask = Uint::<S>::MAX.shr_vartime(Uint::<S>::BITS - self.params.slot_bits);
+
+        let averages = (0..self.params.dimension)
+            .map(|i| {
+                let m = &plaintexts[i / self.slots];
+                let slot = m
+                    .shr_vartime((i % self.slots) as u32 * self.params.slot_bits)
+                    .bitand(&mask);
+                let sum = slot_to_i128(&slot) - count * offset;
+                sum as f64 / scale / count as f64
+            })
+            .collect();
+
+        Ok(averages)
+    }
+
+    pub fn decrypt_average<SK>(
+        &self,
+        sk: &SK,
+        aggregate: &Aggregate<K::Ciphertext>,
+    ) -> Result<Vec<f64>, AggregationError>
+    where
+        SK: DecryptionKey<Uint<S>, Ciphertext = K::Ciphertext>,
+    {
+        let plaintexts = aggregate
+            .ciphertexts
+            .iter()
+            .map(|c| Option::from(sk.try_decrypt(c)).ok_or(AggregationError::InvalidCiphertext))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.decode_average(aggregate, &plaintexts)
+    }
+
+    fn encode(&self, x: f64) -> Option<u64> {
+        let offset = 1i64 << (self.params.value_bits - 1);
+        let scaled = (x * (1u64 << self.params.fractional_bits) as f64).round();
+        if !scaled.is_finite() || scaled.abs() >= offset as f64 {
+            return None;
+        }
+
+        Some((scaled as i64 + offset) as u64)
+    }
+}
+
+fn slot_to_i128<const S: usize>(slot: &Uint<S>) -> i128 {
+    // slots are at most MAX_SLOT_BITS wide, so the value is held by the lowest 128 bits
+    let low = slot.resize::<{ 128 / Limb::BITS as usize }>();
+    low.as_limbs()
+        .iter()
+        .rev()
+        .fold(0i128, |acc, limb| (acc << Limb::BITS) | limb.0 as i128)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::KeyGenerator;
+    use crate::aggregation::{AggregationError, AggregationParams, SecureAggregation};
+    use crate::jl;
+    use crate::sk::SecretKey;
+    use crypto_bigint::{U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+
+    const PARAMS: AggregationParams = AggregationParams {
+        dimension: 30,
+        fractional_bits: 12,
+        value_bits: 24,
+        slot_bits: 40,
+    };
+
+    #[test]
+    fn should_average_updates_with_dropout() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let aggregation = SecureAggregation::new(pk, PARAMS).unwrap();
+        assert_eq!(aggregation.slots_per_ciphertext(), 12);
+        assert_eq!(aggregation.ciphertexts_per_update(), 3);
+        assert_eq!(aggregation.max_summands(), 1 << 16);
+
+        let vectors: Vec<Vec<f64>> = (0..5)
+            .map(|client| (0..30).map(|i| (i as f64 - 15.0) * 0.25 + client as f64).collect())
+            .collect();
+        let updates: Vec<_> = vectors
+            .iter()
+            .enumerate()
+            .map(|(client, values)| aggregation.encrypt_update(client as u64, values, &mut rng).unwrap())
+            .collect();
+
+        // client 3 drops out before its update arrives
+        let mut aggregate = aggregation.empty_aggregate();
+        for update in updates.iter().filter(|update| update.client_id != 3) {
+            aggregation.add(&mut aggregate, update).unwrap();
+        }
+        assert_eq!(
+            aggregation.add(&mut aggregate, &updates[0]),
+            Err(AggregationError::DuplicateClient(0))
+        );
+        assert_eq!(aggregate.dropped_out(&[0, 1, 2, 3, 4]), vec![3]);
+
+        let averages = aggregation.decrypt_average(&sk, &aggregate).unwrap();
+        for (i, average) in averages.iter().enumerate() {
+            let expected = [0, 1, 2, 4].iter().map(|&client| vectors[client][i]).sum::<f64>() / 4.0;
+            assert!((average - expected).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn should_reject_out_of_range_values() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (_, pk) = SmallSecretKey::random(&mut rng);
+        let aggregation = SecureAggregation::new(pk, PARAMS).unwrap();
+
+        let mut values = vec![0.0; 30];
+        values[7] = 2048.0;
+        assert_eq!(
+            aggregation.encrypt_update(0, &values, &mut rng).unwrap_err(),
+            AggregationError::ValueOutOfRange(7)
+        );
+        assert_eq!(
+            aggregation.encrypt_update(0, &values[..29], &mut rng).unwrap_err(),
+            AggregationError::DimensionMismatch
+        );
+
+        let wide = AggregationParams {
+            slot_bits: 600,
+            ..PARAMS
+        };
+        assert_eq!(
+            SecureAggregation::new(pk, wide).unwrap_err(),
+            AggregationError::InvalidParams
+        );
+    }
+
+    #[test]
+    fn should_average_updates_with_joye_libert_key() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let sk = jl::SecretKey::<{ U256::LIMBS }, { U512::LIMBS }>::random_with_plaintext_bits(64, &mut rng);
+        let aggregation = SecureAggregation::new(sk.as_public_key(), PARAMS).unwrap();
+        assert_eq!(aggregation.slots_per_ciphertext(), 1);
+        assert_eq!(aggregation.ciphertexts_per_update(), 30);
+
+        let vectors: Vec<Vec<f64>> = (0..3)
+            .map(|client| (0..30).map(|i| (i as f64 - 15.0) * 0.5 - client as f64).collect())
+            .collect();
+        let mut aggregate = aggregation.empty_aggregate();
+        for (client, values) in vectors.iter().enumerate() {
+            let update = aggregation.encrypt_update(client as u64, values, &mut rng).unwrap();
+            aggregation.add(&mut aggregate, &update).unwrap();
+        }
+
+        let averages = aggregation.decrypt_average(&sk, &aggregate).unwrap();
+        for (i, average) in averages.iter().enumerate() {
+            let expected = vectors.iter().map(|values| values[i]).sum::<f64>() / 3.0;
+            assert!((average - expected).abs() < 1e-3);
+        }
+
+        // 80 bit slots do not fit the 64 bit plaintexts
+        let wide = AggregationParams {
+            slot_bits: 80,
+            ..PARAMS
+        };
+        assert_eq!(
+            SecureAggregation::new(sk.as_public_key(), wide).unwrap_err(),
+            AggregationError::InvalidParams
+        );
+    }
+}
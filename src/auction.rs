@@ -0,0 +1,692 @@
+use crate::comparison::{
+    self, BlindedTests, ComparisonDecryptor, ComparisonError, ComparisonEvaluator, MaskedBits, MaskedDifference,
+    ZeroTestResult,
+};
+use crate::pk::PublicKey;
+use crate::secure_mul::{MulRequest, MulResponse, SecureMulDecryptor, SecureMulError, SecureMulEvaluator};
+use crate::sk::SecretKey;
+use crate::traits::{EncryptionKey, HomomorphicKey};
+use crate::zk::{DecryptionProof, DecryptionProofMode, MembershipProof};
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, Split, Uint};
+use rand_core::CryptoRng;
+use std::collections::HashSet;
+use std::fmt;
+
+// sealed-bid second-price auction: a bid is the encryption of its bits, each with a proof that it encrypts 0 or 1,
+// so the bid ciphertext sum 2^i Enc(b_i) is in range by construction; the auction house scans the bids keeping
+// Enc(highest), Enc(second highest) and Enc(winner) with encrypted comparisons and blinded multiplications against
+// the auctioneer, who holds the key but only sees masked values, and who finally publishes the winner and the
+// clearing price with decryption proofs; ties go to the earlier bid; the bit proofs are bound to the auction id and
+// the bidder so a bid cannot be replayed in another auction or under another name, and a bidder or bit ciphertext
+// showing up twice is rejected
+//
+// the two parties only exchange AuctionRequest and AuctionResponse messages, per bid two comparisons (decompose,
+// then zero tests) and two multiplications; the auctioneer is bound to a single session over a fixed set of bids,
+// answers the messages in protocol order only and publishes one outcome at the very end, after checking that it is
+// a bidder index and a price below 2^bid_bits; it cannot check that the outcome was computed from the bids, so a
+// house deviating from the protocol could still have any ciphertext of that shape decrypted, e.g. a single bid, and
+// both parties are assumed to follow the protocol (semi-honest), the house acting as a decryption oracle otherwise
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AuctionError {
+    BoundTooLarge,
+    BidOutOfRange,
+    NotEnoughBids,
+    InvalidBid(usize),
+    DuplicateBid(usize),
+    InvalidOutcome,
+    UnexpectedMessage,
+    Comparison(ComparisonError),
+    SecureMul(SecureMulError),
+}
+
+impl fmt::Display for AuctionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuctionError::BoundTooLarge => write!(f, "bid bit length does not fit the key"),
+            AuctionError::BidOutOfRange => write!(f, "bid exceeds the bid bit length"),
+            AuctionError::NotEnoughBids => write!(f, "a second-price auction needs at least two bids"),
+            AuctionError::InvalidBid(i) => write!(f, "bid {i} is invalid"),
+            AuctionError::DuplicateBid(i) => write!(f, "bid {i} repeats an earlier bidder or ciphertext"),
+            AuctionError::InvalidOutcome => write!(f, "outcome is not valid for the auction"),
+            AuctionError::UnexpectedMessage => write!(f, "message does not match the protocol state"),
+            AuctionError::Comparison(e) => write!(f, "comparison failed: {e}"),
+            AuctionError::SecureMul(e) => write!(f, "secure multiplication failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for AuctionError {}
+
+impl From<ComparisonError> for AuctionError {
+    fn from(e: ComparisonError) -> Self {
+        AuctionError::Comparison(e)
+    }
+}
+
+impl From<SecureMulError> for AuctionError {
+    fn from(e: SecureMulError) -> Self {
+        AuctionError::SecureMul(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Auction<const S: usize, const D: usize> {
+    key: PublicKey<S, D>,
+    bid_bits: u32,
+    id: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bid<const S: usize, const D: usize> {
+    pub bidder: Vec<u8>,
+    // least significant bit first
+    pub bits: Vec<NonZero<Uint<D>>>,
+    pub proofs: Vec<MembershipProof<S, D>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct EncryptedOutcome<const D: usize> {
+    pub winner: NonZero<Uint<D>>,
+    pub price: NonZero<Uint<D>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuctionResult<const S: usize, const D: usize> {
+    pub winner: usize,
+    pub price: Uint<S>,
+    pub winner_proof: DecryptionProof<S, D>,
+    pub price_proof: DecryptionProof<S, D>,
+}
+
+// messages of the auction house, each comparison round carries the comparisons of the next bid against the
+// highest and against the second highest bid
+#[derive(Debug, Clone)]
+pub enum AuctionRequest<const D: usize> {
+    Decompose([MaskedDifference<D>; 2]),
+    TestZeros([BlindedTests<D>; 2]),
+    Multiply(MulRequest<D>),
+    Publish(EncryptedOutcome<D>),
+}
+
+#[derive(Debug, Clone)]
+pub enum AuctionResponse<const S: usize, const D: usize> {
+    Decomposed([MaskedBits<D>; 2]),
+    ZeroTests([ZeroTestResult<D>; 2]),
+    Multiplied(MulResponse<D>),
+    Published(AuctionResult<S, D>),
+}
+
+#[derive(Debug, Clone)]
+pub struct AuctionHouse<const S: usize, const D: usize> {
+    auction: Auction<S, D>,
+    bids: Vec<NonZero<Uint<D>>>,
+    round: usize,
+    highest: NonZero<Uint<D>>,
+    second: NonZero<Uint<D>>,
+    winner: NonZero<Uint<D>>,
+    state: HouseState<S, D>,
+}
+
+#[derive(Debug, Clone)]
+enum HouseState<const S: usize, const D: usize> {
+    Comparing([ComparisonEvaluator<S, D>; 2]),
+    Raising {
+        above_highest: NonZero<Uint<D>>,
+        evaluator: SecureMulEvaluator<S, D>,
+    },
+    Shifting {
+        raised_second: NonZero<Uint<D>>,
+        evaluator: SecureMulEvaluator<S, D>,
+    },
+    Publishing(EncryptedOutcome<D>),
+    Done,
+}
+
+#[derive(Debug, Clone)]
+pub struct Auctioneer<'a, const H: usize, const S: usize, const D: usize> {
+    sk: &'a SecretKey<H, S, D>,
+    comparison: ComparisonDecryptor<'a, H, S, D>,
+    multiplication: SecureMulDecryptor<'a, H, S, D>,
+    bids: usize,
+    bid_bits: u32,
+    rounds_left: usize,
+    state: AuctioneerState,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum AuctioneerState {
+    Decompose,
+    TestZeros,
+    Raise,
+    Shift,
+    Publish,
+    Done,
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> Auction<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn new(key: PublicKey<S, D>, bid_bits: u32, id: &[u8]) -> Result<Self, AuctionError> {
+        if !comparison::fits(&key.n, bid_bits) {
+            return Err(AuctionError::BoundTooLarge);
+        }
+
+        Ok(Auction {
+            key,
+            bid_bits,
+            id: id.to_vec(),
+        })
+    }
+
+    pub fn bid<R: CryptoRng + ?Sized>(
+        &self,
+        bidder: &[u8],
+        amount: &Uint<S>,
+        rng: &mut R,
+    ) -> Result<Bid<S, D>, AuctionError> {
+        if amount.bits() > self.bid_bits {
+            return Err(AuctionError::BidOutOfRange);
+        }
+
+        let context = self.context(bidder);
+        let (bits, proofs) = (0..self.bid_bits)
+            .map(|i| {
+                let bit = amount.bit_vartime(i);
+                let encryption = self.key.encrypt(&Uint::from_u8(bit as u8), rng);
+                (
+                    encryption.0,
+                    MembershipProof::prove_bit(&self.key, &context, bit, &encryption, rng),
+                )
+            })
+            .unzip();
+        Ok(Bid {
+            bidder: bidder.to_vec(),
+            bits,
+            proofs,
+        })
+    }
+
+    pub fn verify_bid(&self, bid: &Bid<S, D>) -> bool {
+        let context = self.context(&bid.bidder);
+        bid.bits.len() == self.bid_bits as usize
+            && bid.proofs.len() == self.bid_bits as usize
+            && bid
+                .bits
+                .iter()
+                .zip(&bid.proofs)
+                .all(|(c, proof)| proof.verify_bit(&self.key, &context, c))
+    }
+
+    // the auction id is length prefixed so that (id, bidder) pairs cannot collide
+    fn context(&self, bidder: &[u8]) -> Vec<u8> {
+        let mut context = (self.id.len() as u64).to_be_bytes().to_vec();
+        context.extend_from_slice(&self.id);
+        context.extend_from_slice(bidder);
+        context
+    }
+
+    pub fn bid_ciphertext(&self, bid: &Bid<S, D>) -> NonZero<Uint<D>> {
+        let terms: Vec<_> = bid
+            .bits
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (*c, Uint::ONE.shl_vartime(i as u32)))
+            .collect();
+        self.key.ciphertext_multi_mul_scalar(&terms)
+    }
+
+    // both parties run the same checks, the bid ciphertexts are returned in bid order
+    fn check_bids(&self, bids: &[Bid<S, D>]) -> Result<Vec<NonZero<Uint<D>>>, AuctionError> {
+        if bids.len() < 2 {
+            return Err(AuctionError::NotEnoughBids);
+        }
+        if let Some(i) = bids.iter().position(|bid| !self.verify_bid(bid)) {
+            return Err(AuctionError::InvalidBid(i));
+        }
+        let mut bidders = HashSet::new();
+        let mut ciphertexts = HashSet::new();
+        for (i, bid) in bids.iter().enumerate() {
+            if !bidders.insert(bid.bidder.as_slice()) || !bid.bits.iter().all(|c| ciphertexts.insert(*c)) {
+                return Err(AuctionError::DuplicateBid(i));
+            }
+        }
+
+        Ok(bids.iter().map(|bid| self.bid_ciphertext(bid)).collect())
+    }
+
+    pub fn verify_result(&self, outcome: &EncryptedOutcome<D>, result: &AuctionResult<S, D>) -> bool {
+        let winner = Uint::from_u64(result.winner as u64);
+        self.key
+            .verify_decryption(&outcome.winner, &winner, &result.winner_proof)
+            && self
+                .key
+                .verify_decryption(&outcome.price, &result.price, &result.price_proof)
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> AuctionHouse<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn new<R: CryptoRng + ?Sized>(
+        auction: &Auction<S, D>,
+        bids: &[Bid<S, D>],
+        rng: &mut R,
+    ) -> Result<(Self, AuctionRequest<D>), AuctionError> {
+        let bids = auction.check_bids(bids)?;
+        let key = &auction.key;
+
+        let mut house = AuctionHouse {
+            auction: auction.clone(),
+            highest: bids[0],
+            second: key.encrypt(&Uint::ZERO, rng).0,
+            winner: key.encrypt(&Uint::ZERO, rng).0,
+            bids,
+            round: 1,
+            state: HouseState::Done,
+        };
+        let request = house.compare(rng)?;
+        Ok((house, request))
+    }
+
+    // a failed step ends the session, the state is only restored when the response fits it
+    pub fn step<R: CryptoRng + ?Sized>(
+        &mut self,
+        response: &AuctionResponse<S, D>,
+        rng: &mut R,
+    ) -> Result<AuctionRequest<D>, AuctionError> {
+        let key = self.auction.key;
+        match (std::mem::replace(&mut self.state, HouseState::Done), response) {
+            (HouseState::Comparing(mut evaluators), AuctionResponse::Decomposed(bits)) => {
+                let tests = [evaluators[0].blind(&bits[0], rng)?, evaluators[1].blind(&bits[1], rng)?];
+                self.state = HouseState::Comparing(evaluators);
+                Ok(AuctionRequest::TestZeros(tests))
+            }
+            (HouseState::Comparing([highest, second]), AuctionResponse::ZeroTests(results)) => {
+                let above_highest = highest.finish(&results[0])?;
+                let above_second = second.finish(&results[1])?;
+
+                // highest' = highest + t1 (x - highest), winner' = winner + t1 (i - winner)
+                // second' = second + t2 (x - second) + t1 (highest - second - t2 (x - second))
+                let x = self.bids[self.round];
+                let index = key.encrypt_with_nonce(&Uint::from_u64(self.round as u64), &NonZero::ONE);
+                let (evaluator, request) = SecureMulEvaluator::new(
+                    key,
+                    &[
+                        (above_highest, key.ciphertext_sub(&x, &self.highest)),
+                        (above_highest, key.ciphertext_sub(&index, &self.winner)),
+                        (above_second, key.ciphertext_sub(&x, &self.second)),
+                    ],
+                    rng,
+                )?;
+                self.state = HouseState::Raising {
+                    above_highest,
+                    evaluator,
+                };
+                Ok(AuctionRequest::Multiply(request))
+            }
+            (
+                HouseState::Raising {
+                    above_highest,
+                    evaluator,
+                },
+                AuctionResponse::Multiplied(response),
+            ) => {
+                let products = evaluator.finish(response)?;
+                let raised_second = key.ciphertext_add(&self.second, &products[2]);
+                let (evaluator, request) = SecureMulEvaluator::new(
+                    key,
+                    &[(above_highest, key.ciphertext_sub(&self.highest, &raised_second))],
+                    rng,
+                )?;
+
+                self.highest = key.ciphertext_add(&self.highest, &products[0]);
+                self.winner = key.ciphertext_add(&self.winner, &products[1]);
+                self.state = HouseState::Shifting {
+                    raised_second,
+                    evaluator,
+                };
+                Ok(AuctionRequest::Multiply(request))
+            }
+            (
+                HouseState::Shifting {
+                    raised_second,
+                    evaluator,
+                },
+                AuctionResponse::Multiplied(response),
+            ) => {
+                let shifted = evaluator.finish(response)?;
+                self.second = key.ciphertext_add(&raised_second, &shifted[0]);
+                self.round += 1;
+                if self.round < self.bids.len() {
+                    return self.compare(rng);
+                }
+
+                let outcome = EncryptedOutcome {
+                    winner: self.winner,
+                    price: self.second,
+                };
+                self.state = HouseState::Publishing(outcome);
+                Ok(AuctionRequest::Publish(outcome))
+            }
+            _ => Err(AuctionError::UnexpectedMessage),
+        }
+    }
+
+    pub fn finish(self, response: &AuctionResponse<S, D>) -> Result<AuctionResult<S, D>, AuctionError> {
+        let (HouseState::Publishing(outcome), AuctionResponse::Published(result)) = (&self.state, response) else {
+            return Err(AuctionError::UnexpectedMessage);
+        };
+        if !self.auction.verify_result(outcome, result) {
+            return Err(AuctionError::InvalidOutcome);
+        }
+
+        Ok(result.clone())
+    }
+
+    fn compare<R: CryptoRng + ?Sized>(&mut self, rng: &mut R) -> Result<AuctionRequest<D>, AuctionError> {
+        let key = self.auction.key;
+        let bits = self.auction.bid_bits;
+        let x = self.bids[self.round];
+        let (highest, highest_masked) = ComparisonEvaluator::new(key, &self.highest, &x, bits, rng)?;
+        let (second, second_masked) = ComparisonEvaluator::new(key, &self.second, &x, bits, rng)?;
+
+        self.state = HouseState::Comparing([highest, second]);
+        Ok(AuctionRequest::Decompose([highest_masked, second_masked]))
+    }
+}
+
+impl<
+    'a,
+    const H: usize,
+    const H_UNSAT: usize,
+    const S: usize,
+    const S_UNSAT: usize,
+    const D: usize,
+    const D_UNSAT: usize,
+    const Q: usize,
+> Auctioneer<'a, H, S, D>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    // the auctioneer checks the bids on its own and serves exactly one session over them
+    pub fn new(sk: &'a SecretKey<H, S, D>, auction: &Auction<S, D>, bids: &[Bid<S, D>]) -> Result<Self, AuctionError> {
+        let comparison = ComparisonDecryptor::new(sk, auction.bid_bits).map_err(|_| AuctionError::BoundTooLarge)?;
+        let bids = auction.check_bids(bids)?.len();
+
+        Ok(Auctioneer {
+            sk,
+            comparison,
+            multiplication: SecureMulDecryptor::new(sk),
+            bids,
+            bid_bits: auction.bid_bits,
+            rounds_left: bids - 1,
+            state: AuctioneerState::Decompose,
+        })
+    }
+
+    // a failed step ends the session, the state only advances when the request fits it
+    pub fn respond<R: CryptoRng + ?Sized>(
+        &mut self,
+        request: &AuctionRequest<D>,
+        rng: &mut R,
+    ) -> Result<AuctionResponse<S, D>, AuctionError> {
+        let (response, next) = match (std::mem::replace(&mut self.state, AuctioneerState::Done), request) {
+            (AuctioneerState::Decompose, AuctionRequest::Decompose(masked)) => {
+                let bits = [
+                    self.comparison.decompose(&masked[0], rng)?,
+                    self.comparison.decompose(&masked[1], rng)?,
+                ];
+                (AuctionResponse::Decomposed(bits), AuctioneerState::TestZeros)
+            }
+            (AuctioneerState::TestZeros, AuctionRequest::TestZeros(tests)) => {
+                let results = [
+                    self.comparison.test_zeros(&tests[0], rng)?,
+                    self.comparison.test_zeros(&tests[1], rng)?,
+                ];
+                (AuctionResponse::ZeroTests(results), AuctioneerState::Raise)
+            }
+            (AuctioneerState::Raise, AuctionRequest::Multiply(request)) if request.blinded.len() == 3 => {
+                let response = self.multiplication.respond(request, rng)?;
+                (AuctionResponse::Multiplied(response), AuctioneerState::Shift)
+            }
+            (AuctioneerState::Shift, AuctionRequest::Multiply(request)) if request.blinded.len() == 1 => {
+                let response = self.multiplication.respond(request, rng)?;
+                self.rounds_left -= 1;
+                let next = match self.rounds_left {
+                    0 => AuctioneerState::Publish,
+                    _ => AuctioneerState::Decompose,
+                };
+                (AuctionResponse::Multiplied(response), next)
+            }
+            (AuctioneerState::Publish, AuctionRequest::Publish(outcome)) => (
+                AuctionResponse::Published(self.publish(outcome, rng)?),
+                AuctioneerState::Done,
+            ),
+            _ => return Err(AuctionError::UnexpectedMessage),
+        };
+
+        self.state = next;
+        Ok(response)
+    }
+
+    fn publish<R: CryptoRng + ?Sized>(
+        &self,
+        outcome: &EncryptedOutcome<D>,
+        rng: &mut R,
+    ) -> Result<AuctionResult<S, D>, AuctionError> {
+        let (winner, winner_proof) = self
+            .sk
+            .prove_decryption(&outcome.winner, DecryptionProofMode::ZeroKnowledge, rng)
+            .ok_or(AuctionError::InvalidOutcome)?;
+        let (price, price_proof) = self
+            .sk
+            .prove_decryption(&outcome.price, DecryptionProofMode::ZeroKnowledge, rng)
+            .ok_or(AuctionError::InvalidOutcome)?;
+
+        let winner = usize::try_from(winner.as_words()[0])
+            .ok()
+            .filter(|&index| winner.bits() <= 64 && index < self.bids && price.bits() <= self.bid_bits)
+            .ok_or(AuctionError::InvalidOutcome)?;
+        Ok(AuctionResult {
+            winner,
+            price,
+            winner_proof,
+            price_proof,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::auction::{
+        Auction, AuctionError, AuctionHouse, AuctionRequest, AuctionResult, Auctioneer, Bid, EncryptedOutcome,
+    };
+    use crate::sk::SecretKey;
+    use crate::{EncryptionKey, KeyGenerator};
+    use crypto_bigint::{U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+    type SmallAuction = Auction<{ U512::LIMBS }, { U1024::LIMBS }>;
+    type SmallBid = Bid<{ U512::LIMBS }, { U1024::LIMBS }>;
+
+    // drives both parties, every request and response would travel over the wire
+    fn run(
+        sk: &SmallSecretKey,
+        auction: &SmallAuction,
+        bids: &[SmallBid],
+        rng: &mut ChaCha8Rng,
+    ) -> Result<
+        (
+            EncryptedOutcome<{ U1024::LIMBS }>,
+            AuctionResult<{ U512::LIMBS }, { U1024::LIMBS }>,
+        ),
+        AuctionError,
+    > {
+        let mut auctioneer = Auctioneer::new(sk, auction, bids)?;
+        let (mut house, mut request) = AuctionHouse::new(auction, bids, rng)?;
+        loop {
+            let response = auctioneer.respond(&request, rng)?;
+            if let AuctionRequest::Publish(outcome) = request {
+                return Ok((outcome, house.finish(&response)?));
+            }
+            request = house.step(&response, rng)?;
+        }
+    }
+
+    #[test]
+    fn should_resolve_second_price_auction() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let auction = Auction::new(pk, 12, b"lot 1").unwrap();
+
+        for (amounts, winner, price) in [(vec![5u64, 40, 17], 1, 17), (vec![300, 1200, 800, 1200], 1, 1200)] {
+            let bids: Vec<_> = amounts
+                .iter()
+                .enumerate()
+                .map(|(i, amount)| auction.bid(&[i as u8], &U512::from_u64(*amount), &mut rng).unwrap())
+                .collect();
+            let (outcome, result) = run(&sk, &auction, &bids, &mut rng).unwrap();
+
+            assert_eq!(result.winner, winner);
+            assert_eq!(result.price, U512::from_u64(price));
+            assert!(auction.verify_result(&outcome, &result));
+        }
+    }
+
+    #[test]
+    fn should_reject_invalid_bids() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let auction = Auction::new(pk, 8, b"lot 1").unwrap();
+
+        assert_eq!(
+            auction.bid(b"alice", &U512::from_u64(256), &mut rng).unwrap_err(),
+            AuctionError::BidOutOfRange
+        );
+
+        let honest = auction.bid(b"alice", &U512::from_u64(10), &mut rng).unwrap();
+        let mut forged = auction.bid(b"bob", &U512::from_u64(20), &mut rng).unwrap();
+        forged.bits[7] = auction.bid(b"bob", &U512::from_u64(2), &mut rng).unwrap().bits[1];
+        assert!(auction.verify_bid(&honest));
+        assert!(!auction.verify_bid(&forged));
+
+        let bids = [honest, forged];
+        assert_eq!(
+            AuctionHouse::new(&auction, &bids, &mut rng).unwrap_err(),
+            AuctionError::InvalidBid(1)
+        );
+        assert_eq!(
+            Auctioneer::new(&sk, &auction, &bids).unwrap_err(),
+            AuctionError::InvalidBid(1)
+        );
+    }
+
+    #[test]
+    fn should_reject_replayed_bids() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let auction = Auction::new(pk, 8, b"lot 1").unwrap();
+        let other = Auction::new(pk, 8, b"lot 2").unwrap();
+
+        let alice = auction.bid(b"alice", &U512::from_u64(100), &mut rng).unwrap();
+        let carol = auction.bid(b"carol", &U512::from_u64(50), &mut rng).unwrap();
+
+        // a copied bid claimed by another bidder or moved to another auction no longer verifies
+        let mut stolen = alice.clone();
+        stolen.bidder = b"bob".to_vec();
+        assert!(!auction.verify_bid(&stolen));
+        assert!(!other.verify_bid(&alice));
+        assert_eq!(
+            run(&sk, &auction, &[alice.clone(), carol.clone(), stolen], &mut rng).unwrap_err(),
+            AuctionError::InvalidBid(2)
+        );
+
+        // the same bid submitted twice, and a bid lifting a bit ciphertext with its proof from another bid
+        assert_eq!(
+            run(&sk, &auction, &[alice.clone(), carol.clone(), alice.clone()], &mut rng).unwrap_err(),
+            AuctionError::DuplicateBid(2)
+        );
+        let mut copycat = auction.bid(b"bob", &U512::from_u64(1), &mut rng).unwrap();
+        copycat.bits[3] = carol.bits[3];
+        copycat.proofs[3] = carol.proofs[3].clone();
+        assert_eq!(
+            run(&sk, &auction, &[alice, carol, copycat], &mut rng).unwrap_err(),
+            AuctionError::InvalidBid(2)
+        );
+    }
+
+    #[test]
+    fn should_publish_only_at_the_end_of_the_session() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let auction = Auction::new(pk, 8, b"lot 1").unwrap();
+        let bids: Vec<_> = [b"alice", b"carol"]
+            .iter()
+            .enumerate()
+            .map(|(i, bidder)| auction.bid(*bidder, &U512::from_u64(10 * i as u64), &mut rng).unwrap())
+            .collect();
+
+        // asking for the decryption of a bid before the comparisons ends the session
+        let mut auctioneer = Auctioneer::new(&sk, &auction, &bids).unwrap();
+        let probe = EncryptedOutcome {
+            winner: pk.encrypt(&U512::ZERO, &mut rng).0,
+            price: auction.bid_ciphertext(&bids[0]),
+        };
+        assert_eq!(
+            auctioneer
+                .respond(&AuctionRequest::Publish(probe), &mut rng)
+                .unwrap_err(),
+            AuctionError::UnexpectedMessage
+        );
+        let (_, request) = AuctionHouse::new(&auction, &bids, &mut rng).unwrap();
+        assert_eq!(
+            auctioneer.respond(&request, &mut rng).unwrap_err(),
+            AuctionError::UnexpectedMessage
+        );
+
+        // at the end only a winner among the bidders and a price in range are published, and only once
+        let mut auctioneer = Auctioneer::new(&sk, &auction, &bids).unwrap();
+        let (mut house, mut request) = AuctionHouse::new(&auction, &bids, &mut rng).unwrap();
+        while !matches!(request, AuctionRequest::Publish(_)) {
+            let response = auctioneer.respond(&request, &mut rng).unwrap();
+            request = house.step(&response, &mut rng).unwrap();
+        }
+        let forged = EncryptedOutcome {
+            winner: pk.encrypt(&U512::from_u64(2), &mut rng).0,
+            price: probe.price,
+        };
+        assert_eq!(
+            auctioneer
+                .clone()
+                .respond(&AuctionRequest::Publish(forged), &mut rng)
+                .unwrap_err(),
+            AuctionError::InvalidOutcome
+        );
+        let response = auctioneer.respond(&request, &mut rng).unwrap();
+        assert_eq!(
+            auctioneer.respond(&request, &mut rng).unwrap_err(),
+            AuctionError::UnexpectedMessage
+        );
+        assert_eq!(house.finish(&response).unwrap().winner, 1);
+    }
+}
@@ -0,0 +1,448 @@
+use crate::pk::PublicPrecomputation;
+use crate::sk::SecretKey;
+use crate::traits::{DecryptionKey, EncryptionKey, Key};
+use crate::utils::{wide_div, wider_rem};
+use crypto_bigint::modular::{MontyForm, SafeGcdInverter};
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, RandomMod, Split, Uint};
+use crypto_primes::RandomPrimeWithRng;
+use rand_core::CryptoRng;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeLess, CtOption};
+
+// Bresson-Catalano-Pointcheval: users of a group (n, g) hold h = g^a and encrypt (A, B) = (g^r, h^r (1 + n)^m) mod n^2,
+// a user decrypts with m = L(B / A^a); the owner of the factorization decrypts any ciphertext of any user, writing
+// dec for paillier decryption, the discrete logarithm to the base 1 + n, dec(h) = a dec(g) and dec(A) = r dec(g)
+// so that m = dec(B) - dec(h) dec(A) / dec(g) mod n; nonces live modulo the secret order of g, hence there is no
+// HomomorphicKey, ciphertexts can still be added and scaled
+
+#[derive(Debug, Copy, Clone)]
+pub struct Group<const S: usize, const D: usize> {
+    n: Odd<Uint<S>>,
+    g: NonZero<Uint<D>>,
+    precomputation: PublicPrecomputation<S, D>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct UserPublicKey<const S: usize, const D: usize> {
+    group: Group<S, D>,
+    h: NonZero<Uint<D>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct UserSecretKey<const S: usize, const D: usize> {
+    pk: UserPublicKey<S, D>,
+    a: Uint<D>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Ciphertext<const D: usize> {
+    pub a: NonZero<Uint<D>>,
+    pub b: NonZero<Uint<D>>,
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> Group<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    // g generates QR_{n^2}, which is cyclic of order n p' q' for safe primes p = 2p' + 1 and q = 2q' + 1; a random
+    // square has maximal order unless its 1 + n component is not a generator (order misses p or q) or it is 1 modulo
+    // p or q (order misses p' or q')
+    pub fn new<const H: usize, const H_UNSAT: usize, R: CryptoRng + ?Sized>(
+        master: &SecretKey<H, S, D>,
+        rng: &mut R,
+    ) -> Self
+    where
+        Uint<H>: Concat<Output = Uint<S>>,
+        Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+        Uint<S>: Split<Output = Uint<H>>,
+    {
+        if !master.p.as_ref().is_safe_prime_with_rng(rng) || !master.q.as_ref().is_safe_prime_with_rng(rng) {
+            panic!("the master key must be built from safe primes");
+        }
+
+        let n = master.pk.n;
+        let nn_monty_params = master.pk.precomputation.nn_monty_params;
+        loop {
+            let alpha = Uint::<D>::random_mod(rng, nn_monty_params.modulus().as_nz_ref());
+            let g = MontyForm::new(&alpha, nn_monty_params).square().retrieve();
+            let Some(g) = Option::<NonZero<Uint<D>>>::from(g.to_nz()) else {
+                continue;
+            };
+            if !bool::from(master.ciphertext_is_valid(&g)) {
+                continue;
+            }
+            if wider_rem(&g, master.p.as_nz_ref()) == Uint::ONE || wider_rem(&g, master.q.as_nz_ref()) == Uint::ONE {
+                continue;
+            }
+            if bool::from(master.decrypt(&g).inv_odd_mod(&n).is_some()) {
+                return Self::from_parts_unchecked(n, g);
+            }
+        }
+    }
+
+    pub fn from_parts_unchecked(n: Odd<Uint<S>>, g: NonZero<Uint<D>>) -> Self {
+        Group {
+            n,
+            g,
+            precomputation: PublicPrecomputation::new(&n),
+        }
+    }
+
+    pub fn n(&self) -> &Odd<Uint<S>> {
+        &self.n
+    }
+
+    pub fn g(&self) -> &NonZero<Uint<D>> {
+        &self.g
+    }
+
+    pub fn generate_user_key<R: CryptoRng + ?Sized>(&self, rng: &mut R) -> (UserSecretKey<S, D>, UserPublicKey<S, D>) {
+        let mut a = Uint::ZERO;
+        while a == Uint::ZERO {
+            a = Uint::random_mod(rng, self.precomputation.nn_monty_params.modulus().as_nz_ref());
+        }
+
+        let pk = UserPublicKey {
+            group: *self,
+            h: self.pow(&self.g, &a),
+        };
+        (UserSecretKey { pk, a }, pk)
+    }
+
+    // decrypts a ciphertext of any user of the group with the factorization of n
+    pub fn master_decrypt<const H: usize, const H_UNSAT: usize>(
+        &self,
+        master: &SecretKey<H, S, D>,
+        key: &UserPublicKey<S, D>,
+        c: &Ciphertext<D>,
+    ) -> Option<Uint<S>>
+    where
+        Uint<H>: Concat<Output = Uint<S>>,
+        Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+        Uint<S>: Split<Output = Uint<H>>,
+    {
+        if master.pk.n != self.n || key.group.n != self.n || key.group.g != self.g {
+            return None;
+        }
+        let dec = |x: &NonZero<Uint<D>>| Option::<Uint<S>>::from(master.try_decrypt(x));
+
+        let dec_g_inv = Option::<Uint<S>>::from(dec(&self.g)?.inv_odd_mod(&self.n))?;
+        let dec_h = dec(&key.h)?;
+        let (dec_a, dec_b) = (dec(&c.a)?, dec(&c.b)?);
+
+        let n = self.n.as_nz_ref();
+        let gamma = dec_h.mul_mod(&dec_a, n).mul_mod(&dec_g_inv, n);
+        Some(dec_b.sub_mod(&gamma, &self.n))
+    }
+
+    fn pow<const R: usize>(&self, x: &Uint<D>, e: &Uint<R>) -> NonZero<Uint<D>> {
+        let x_monty_form = MontyForm::new(x, self.precomputation.nn_monty_params);
+        x_monty_form.pow(e).retrieve().to_nz().expect("units are non zero")
+    }
+
+    fn mul(&self, x: &Uint<D>, y: &Uint<D>) -> NonZero<Uint<D>> {
+        let params = self.precomputation.nn_monty_params;
+        let product = MontyForm::new(x, params) * MontyForm::new(y, params);
+        product.retrieve().to_nz().expect("units are non zero")
+    }
+
+    fn is_unit(&self, x: &NonZero<Uint<D>>) -> Choice {
+        let nn = self.precomputation.nn_monty_params.modulus();
+        x.ct_lt(nn) & x.gcd(nn).ct_eq(&Uint::ONE)
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> UserPublicKey<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn group(&self) -> &Group<S, D> {
+        &self.group
+    }
+
+    pub fn h(&self) -> &NonZero<Uint<D>> {
+        &self.h
+    }
+
+    pub fn random_nonce<R: CryptoRng + ?Sized>(&self, rng: &mut R) -> NonZero<Uint<D>> {
+        let nn = self.group.precomputation.nn_monty_params.modulus();
+        let mut r = Uint::ZERO;
+        while r == Uint::ZERO {
+            r = Uint::random_mod(rng, nn.as_nz_ref());
+        }
+
+        r.to_nz().expect("r is non zero")
+    }
+
+    pub fn ciphertext_add(&self, cl: &Ciphertext<D>, cr: &Ciphertext<D>) -> Ciphertext<D> {
+        Ciphertext {
+            a: self.group.mul(&cl.a, &cr.a),
+            b: self.group.mul(&cl.b, &cr.b),
+        }
+    }
+
+    pub fn ciphertext_add_plain(&self, c: &Ciphertext<D>, m: &Uint<S>) -> Ciphertext<D> {
+        Ciphertext {
+            a: c.a,
+            b: self.group.mul(&c.b, &self.one_plus_n_to(m)),
+        }
+    }
+
+    pub fn ciphertext_mul_scalar(&self, c: &Ciphertext<D>, s: &Uint<S>) -> Ciphertext<D> {
+        Ciphertext {
+            a: self.group.pow(&c.a, s),
+            b: self.group.pow(&c.b, s),
+        }
+    }
+
+    fn one_plus_n_to(&self, m: &Uint<S>) -> Uint<D> {
+        // (1 + n)^m = 1 + m n mod n^2
+        let m_reduced = m.rem(self.group.n.as_nz_ref());
+        self.group.n.widening_mul(&m_reduced).wrapping_add(&Uint::ONE)
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> Key<Uint<S>>
+    for UserPublicKey<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    type Ciphertext = Ciphertext<D>;
+    type Nonce = NonZero<Uint<D>>;
+
+    fn plaintext_is_valid(&self, m: &Uint<S>) -> Choice {
+        m.ct_lt(&self.group.n)
+    }
+
+    fn plaintext_eq(&self, ml: &Uint<S>, mr: &Uint<S>) -> Choice {
+        self.plaintext_is_valid(ml) & self.plaintext_is_valid(mr) & ml.ct_eq(mr)
+    }
+
+    fn ciphertext_is_valid(&self, c: &Self::Ciphertext) -> Choice {
+        self.group.is_unit(&c.a) & self.group.is_unit(&c.b)
+    }
+
+    fn ciphertext_eq(&self, cl: &Self::Ciphertext, cr: &Self::Ciphertext) -> Choice {
+        self.ciphertext_is_valid(cl) & self.ciphertext_is_valid(cr) & cl.a.ct_eq(&cr.a) & cl.b.ct_eq(&cr.b)
+    }
+
+    fn nonce_is_valid(&self, r: &Self::Nonce) -> Choice {
+        r.ct_lt(self.group.precomputation.nn_monty_params.modulus())
+    }
+
+    fn nonce_eq(&self, rl: &Self::Nonce, rr: &Self::Nonce) -> Choice {
+        self.nonce_is_valid(rl) & self.nonce_is_valid(rr) & rl.ct_eq(rr)
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> EncryptionKey<Uint<S>>
+    for UserPublicKey<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    fn encrypt_with_nonce(&self, m: &Uint<S>, r: &Self::Nonce) -> Self::Ciphertext {
+        let a = self.group.pow(&self.group.g, r.as_ref());
+        let h_to_r = self.group.pow(&self.h, r.as_ref());
+        let b = self.group.mul(&h_to_r, &self.one_plus_n_to(m));
+
+        Ciphertext { a, b }
+    }
+
+    fn encrypt<R: CryptoRng + ?Sized>(&self, m: &Uint<S>, rng: &mut R) -> (Self::Ciphertext, Self::Nonce) {
+        let r = self.random_nonce(rng);
+        let c = self.encrypt_with_nonce(m, &r);
+        (c, r)
+    }
+}
+
+impl<const S: usize, const D: usize> UserSecretKey<S, D> {
+    pub fn as_public_key(&self) -> UserPublicKey<S, D> {
+        self.pk
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> Key<Uint<S>>
+    for UserSecretKey<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    type Ciphertext = Ciphertext<D>;
+    type Nonce = NonZero<Uint<D>>;
+
+    fn plaintext_is_valid(&self, m: &Uint<S>) -> Choice {
+        self.pk.plaintext_is_valid(m)
+    }
+
+    fn plaintext_eq(&self, ml: &Uint<S>, mr: &Uint<S>) -> Choice {
+        self.pk.plaintext_eq(ml, mr)
+    }
+
+    fn ciphertext_is_valid(&self, c: &Self::Ciphertext) -> Choice {
+        self.pk.ciphertext_is_valid(c)
+    }
+
+    fn ciphertext_eq(&self, cl: &Self::Ciphertext, cr: &Self::Ciphertext) -> Choice {
+        self.pk.ciphertext_eq(cl, cr)
+    }
+
+    fn nonce_is_valid(&self, r: &Self::Nonce) -> Choice {
+        self.pk.nonce_is_valid(r)
+    }
+
+    fn nonce_eq(&self, rl: &Self::Nonce, rr: &Self::Nonce) -> Choice {
+        self.pk.nonce_eq(rl, rr)
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> DecryptionKey<Uint<S>>
+    for UserSecretKey<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    fn decrypt(&self, c: &Self::Ciphertext) -> Uint<S> {
+        // m = L(B / A^a), L(x) = (x - 1) / n
+        let group = &self.pk.group;
+        let a_to_a = group.pow(&c.a, &self.a);
+        let a_to_a_inv = a_to_a
+            .inv_odd_mod(group.precomputation.nn_monty_params.modulus())
+            .expect("units are invertible");
+        let x = group.mul(&c.b, &a_to_a_inv);
+
+        wide_div(&x.wrapping_sub(&Uint::ONE), group.n.as_nz_ref())
+    }
+
+    fn try_decrypt(&self, c: &Self::Ciphertext) -> CtOption<Uint<S>> {
+        // A^a of an invalid ciphertext may not be invertible, so it is swapped for a trivial one
+        let is_valid = self.ciphertext_is_valid(c);
+        let trivial = Ciphertext {
+            a: NonZero::ONE,
+            b: NonZero::ONE,
+        };
+        let c_checked = Ciphertext {
+            a: NonZero::conditional_select(&trivial.a, &c.a, is_valid),
+            b: NonZero::conditional_select(&trivial.b, &c.b, is_valid),
+        };
+
+        CtOption::new(self.decrypt(&c_checked), is_valid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bcp::Group;
+    use crate::pk::PublicKey;
+    use crate::sk::SecretKey;
+    use crate::{DecryptionKey, EncryptionKey, KeyGenerator};
+    use crypto_bigint::modular::MontyForm;
+    use crypto_bigint::{U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+
+    fn safe_prime_key(rng: &mut ChaCha8Rng) -> (SmallSecretKey, PublicKey<{ U512::LIMBS }, { U1024::LIMBS }>) {
+        let (sk, _) = SmallSecretKey::builder().safe_primes(true).generate(rng);
+        let pk = sk.as_public_key();
+        (sk, pk)
+    }
+
+    #[test]
+    fn should_decrypt_with_user_and_master_key() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (master, pk) = safe_prime_key(&mut rng);
+        let group = Group::new(&master, &mut rng);
+        let (alice_sk, alice_pk) = group.generate_user_key(&mut rng);
+        let (_, bob_pk) = group.generate_user_key(&mut rng);
+
+        let m = pk.random_plaintext(&mut rng);
+        let (c, _) = alice_pk.encrypt(&m, &mut rng);
+        assert_eq!(alice_sk.decrypt(&c), m);
+        assert_eq!(group.master_decrypt(&master, &alice_pk, &c), Some(m));
+
+        let (c, _) = bob_pk.encrypt(&m, &mut rng);
+        assert_ne!(alice_sk.decrypt(&c), m);
+        assert_eq!(group.master_decrypt(&master, &bob_pk, &c), Some(m));
+    }
+
+    #[test]
+    fn should_generate_group_of_maximal_order() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (master, _) = safe_prime_key(&mut rng);
+        let group = Group::new(&master, &mut rng);
+
+        // |QR_{n^2}| = n p' q', g has maximal order if no maximal proper divisor of it kills g
+        let pp = master.p.shr_vartime(1);
+        let qp = master.q.shr_vartime(1);
+        let order: U1024 = group.n().widening_mul(&pp.widening_mul(&qp));
+        let g_to = |e: &U1024| {
+            MontyForm::new(group.g(), master.pk.precomputation.nn_monty_params)
+                .pow(e)
+                .retrieve()
+        };
+        assert_eq!(g_to(&order), U1024::ONE);
+        for l in [*master.p.as_ref(), *master.q.as_ref(), pp, qp] {
+            let e = order.wrapping_div(&l.resize::<{ U1024::LIMBS }>().to_nz().unwrap());
+            assert_ne!(g_to(&e), U1024::ONE);
+        }
+    }
+
+    #[test]
+    fn should_add_and_scale_ciphertexts() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (master, pk) = safe_prime_key(&mut rng);
+        let group = Group::new(&master, &mut rng);
+        let (user_sk, user_pk) = group.generate_user_key(&mut rng);
+        let n = pk.n.as_nz_ref();
+
+        let m1 = pk.random_plaintext(&mut rng);
+        let m2 = pk.random_plaintext(&mut rng);
+        let s = pk.random_plaintext(&mut rng);
+        let c1 = user_pk.encrypt(&m1, &mut rng).0;
+        let c2 = user_pk.encrypt(&m2, &mut rng).0;
+
+        let sum = user_pk.ciphertext_add(&c1, &c2);
+        assert_eq!(user_sk.decrypt(&sum), m1.add_mod(&m2, &pk.n));
+        let shifted = user_pk.ciphertext_add_plain(&c1, &m2);
+        assert_eq!(user_sk.decrypt(&shifted), m1.add_mod(&m2, &pk.n));
+        let scaled = user_pk.ciphertext_mul_scalar(&c1, &s);
+        assert_eq!(
+            group.master_decrypt(&master, &user_pk, &scaled),
+            Some(m1.mul_mod(&s, n))
+        );
+    }
+
+    #[test]
+    fn should_reject_foreign_master_key() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (master, pk) = safe_prime_key(&mut rng);
+        let (other, _) = SmallSecretKey::random(&mut rng);
+        let group = Group::new(&master, &mut rng);
+        let (_, user_pk) = group.generate_user_key(&mut rng);
+
+        let (c, _) = user_pk.encrypt(&pk.random_plaintext(&mut rng), &mut rng);
+        assert_eq!(group.master_decrypt(&other, &user_pk, &c), None);
+    }
+}
@@ -0,0 +1,366 @@
+use base64ct::{Base64, Encoding};
+use clap::{Parser, Subcommand, ValueEnum};
+use crypto_bigint::{NonZero, U2048, U4096, U8192, Uint};
+use crypto_paillier::pkcs8::{
+    DecodePrivateKey, EncodePrivateKey, LineEnding, spki::DecodePublicKey, spki::EncodePublicKey,
+};
+use crypto_paillier::{
+    DecryptionKey, EncryptionKey, HomomorphicKey, Key, KeyGenerator, OpeningKey, PaillierPublicKey2048,
+    PaillierPublicKey4096, PaillierSecretKey2048, PaillierSecretKey4096,
+};
+use rand_core::{OsRng, TryRngCore};
+use std::fmt;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(
+    name = "paillier",
+    version,
+    about = "Paillier key generation, encryption and decryption"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a secret key
+    Keygen {
+        /// Key length in bits, 2048 or 4096 (3072 bit keys are not supported)
+        #[arg(long, default_value_t = 2048, value_parser = parse_bits)]
+        bits: u32,
+        #[arg(long, value_enum, default_value_t = KeyFormat::Pem)]
+        format: KeyFormat,
+    },
+    /// Extract the public key from a secret key
+    Pubkey {
+        #[arg(long)]
+        key: PathBuf,
+        #[arg(long, value_enum, default_value_t = KeyFormat::Pem)]
+        format: KeyFormat,
+    },
+    /// Encrypt a decimal (or 0x prefixed hexadecimal) plaintext
+    Encrypt {
+        #[arg(long)]
+        key: PathBuf,
+        #[arg(long, value_enum, default_value_t = CiphertextEncoding::Hex)]
+        encoding: CiphertextEncoding,
+        plaintext: String,
+    },
+    /// Decrypt a ciphertext
+    Decrypt {
+        #[arg(long)]
+        key: PathBuf,
+        #[arg(long, value_enum, default_value_t = CiphertextEncoding::Hex)]
+        encoding: CiphertextEncoding,
+        ciphertext: String,
+    },
+    /// Decrypt a ciphertext and recover its nonce
+    Open {
+        #[arg(long)]
+        key: PathBuf,
+        #[arg(long, value_enum, default_value_t = CiphertextEncoding::Hex)]
+        encoding: CiphertextEncoding,
+        ciphertext: String,
+    },
+    /// Homomorphically add two ciphertexts
+    Add {
+        #[arg(long)]
+        key: PathBuf,
+        #[arg(long, value_enum, default_value_t = CiphertextEncoding::Hex)]
+        encoding: CiphertextEncoding,
+        ciphertext_lhs: String,
+        ciphertext_rhs: String,
+    },
+    /// Homomorphically multiply a ciphertext by a decimal (or 0x prefixed hexadecimal) scalar
+    MulScalar {
+        #[arg(long)]
+        key: PathBuf,
+        #[arg(long, value_enum, default_value_t = CiphertextEncoding::Hex)]
+        encoding: CiphertextEncoding,
+        ciphertext: String,
+        scalar: String,
+    },
+    /// Re-randomize a ciphertext without changing its plaintext
+    Rerandomize {
+        #[arg(long)]
+        key: PathBuf,
+        #[arg(long, value_enum, default_value_t = CiphertextEncoding::Hex)]
+        encoding: CiphertextEncoding,
+        ciphertext: String,
+    },
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum KeyFormat {
+    Pem,
+    Json,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum CiphertextEncoding {
+    Hex,
+    Base64,
+}
+
+enum CliError {
+    Io(std::io::Error),
+    InvalidKey,
+    InvalidCiphertext,
+    InvalidPlaintext,
+}
+
+impl CliError {
+    fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Io(_) => 1,
+            CliError::InvalidKey => 3,
+            CliError::InvalidCiphertext => 4,
+            CliError::InvalidPlaintext => 5,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Io(e) => write!(f, "cannot read key: {e}"),
+            CliError::InvalidKey => write!(
+                f,
+                "key is not a valid 2048 or 4096 bit Paillier key in PEM or JSON format"
+            ),
+            CliError::InvalidCiphertext => write!(f, "ciphertext is malformed or not valid for the key"),
+            CliError::InvalidPlaintext => write!(f, "plaintext or scalar is malformed or not smaller than n"),
+        }
+    }
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(e: std::io::Error) -> Self {
+        CliError::Io(e)
+    }
+}
+
+fn parse_bits(s: &str) -> Result<u32, String> {
+    match s {
+        "2048" => Ok(2048),
+        "4096" => Ok(4096),
+        // the arithmetic modulo n^2 would need a 12288 bit integer, which crypto-bigint does not provide
+        "3072" => Err("3072 bit keys are not supported, use 2048 or 4096".into()),
+        _ => Err("key length must be 2048 or 4096".into()),
+    }
+}
+
+fn parse_integer<const L: usize>(s: &str) -> Result<Uint<L>, CliError> {
+    match s.strip_prefix("0x") {
+        Some(hex) => Uint::from_str_radix_vartime(hex, 16),
+        None => Uint::from_str_radix_vartime(s, 10),
+    }
+    .map_err(|_| CliError::InvalidPlaintext)
+}
+
+fn encode_bytes(bytes: &[u8], encoding: CiphertextEncoding) -> String {
+    match encoding {
+        CiphertextEncoding::Hex => bytes.iter().map(|b| format!("{b:02x}")).collect(),
+        CiphertextEncoding::Base64 => Base64::encode_string(bytes),
+    }
+}
+
+fn decode_bytes(s: &str, encoding: CiphertextEncoding) -> Result<Vec<u8>, CliError> {
+    match encoding {
+        CiphertextEncoding::Hex => {
+            if !s.is_ascii() || s.len() % 2 != 0 {
+                return Err(CliError::InvalidCiphertext);
+            }
+            (0..s.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
+                .collect::<Result<_, _>>()
+                .map_err(|_| CliError::InvalidCiphertext)
+        }
+        CiphertextEncoding::Base64 => Base64::decode_vec(s).map_err(|_| CliError::InvalidCiphertext),
+    }
+}
+
+// Every key size has its own concrete types, so the commands are stamped out per size.
+macro_rules! impl_commands {
+    ($module:ident, $secret_key:ty, $public_key:ty, $plaintext:ty, $ciphertext:ty) => {
+        mod $module {
+            use super::*;
+
+            pub(crate) fn keygen(format: KeyFormat) -> Result<String, CliError> {
+                let (sk, _) = <$secret_key>::random(&mut OsRng.unwrap_err());
+                match format {
+                    KeyFormat::Pem => Ok(sk
+                        .to_pkcs8_pem(LineEnding::LF)
+                        .map_err(|_| CliError::InvalidKey)?
+                        .to_string()),
+                    KeyFormat::Json => Ok(sk.to_jwk()),
+                }
+            }
+
+            pub(crate) fn secret_key(key: &str) -> Option<$secret_key> {
+                match key.trim_start().starts_with('{') {
+                    true => <$secret_key>::from_jwk(key).ok(),
+                    false => <$secret_key>::from_pkcs8_pem(key).ok(),
+                }
+            }
+
+            pub(crate) fn public_key(key: &str) -> Option<$public_key> {
+                let pk = match key.trim_start().starts_with('{') {
+                    true => <$public_key>::from_jwk(key).ok(),
+                    false => <$public_key>::from_public_key_pem(key).ok(),
+                };
+                pk.or_else(|| secret_key(key).map(|sk| sk.as_public_key()))
+            }
+
+            pub(crate) fn run(command: &Command, key: &str) -> Option<Result<String, CliError>> {
+                match command {
+                    Command::Keygen { .. } => None,
+                    Command::Pubkey { format, .. } => {
+                        let pk = public_key(key)?;
+                        Some(match format {
+                            KeyFormat::Pem => pk
+                                .to_public_key_pem(LineEnding::LF)
+                                .map_err(|_| CliError::InvalidKey),
+                            KeyFormat::Json => Ok(pk.to_jwk()),
+                        })
+                    }
+                    Command::Encrypt {
+                        encoding, plaintext, ..
+                    } => {
+                        let pk = public_key(key)?;
+                        Some(parse_plaintext(&pk, plaintext).map(|m| {
+                            let (c, _) = pk.encrypt(&m, &mut OsRng.unwrap_err());
+                            encode_bytes(&pk.ciphertext_to_bytes(&c), *encoding)
+                        }))
+                    }
+                    Command::Decrypt {
+                        encoding, ciphertext, ..
+                    } => {
+                        let sk = secret_key(key)?;
+                        let pk = sk.as_public_key();
+                        Some(
+                            parse_ciphertext(&pk, ciphertext, *encoding)
+                                .map(|c| sk.decrypt(&c).to_string_radix_vartime(10)),
+                        )
+                    }
+                    Command::Open {
+                        encoding, ciphertext, ..
+                    } => {
+                        let sk = secret_key(key)?;
+                        let pk = sk.as_public_key();
+                        Some(parse_ciphertext(&pk, ciphertext, *encoding).map(|c| {
+                            let (m, r) = sk.open(&c);
+                            format!(
+                                "{}\n{}",
+                                m.to_string_radix_vartime(10),
+                                encode_bytes(&pk.nonce_to_bytes(&r), *encoding)
+                            )
+                        }))
+                    }
+                    Command::Add {
+                        encoding,
+                        ciphertext_lhs,
+                        ciphertext_rhs,
+                        ..
+                    } => {
+                        let pk = public_key(key)?;
+                        Some((|| {
+                            let cl = parse_ciphertext(&pk, ciphertext_lhs, *encoding)?;
+                            let cr = parse_ciphertext(&pk, ciphertext_rhs, *encoding)?;
+                            Ok(encode_bytes(
+                                &pk.ciphertext_to_bytes(&pk.ciphertext_add(&cl, &cr)),
+                                *encoding,
+                            ))
+                        })())
+                    }
+                    Command::MulScalar {
+                        encoding,
+                        ciphertext,
+                        scalar,
+                        ..
+                    } => {
+                        let pk = public_key(key)?;
+                        Some((|| {
+                            let c = parse_ciphertext(&pk, ciphertext, *encoding)?;
+                            let s = parse_plaintext(&pk, scalar)?;
+                            Ok(encode_bytes(
+                                &pk.ciphertext_to_bytes(&pk.ciphertext_mul_scalar(&c, &s)),
+                                *encoding,
+                            ))
+                        })())
+                    }
+                    Command::Rerandomize {
+                        encoding, ciphertext, ..
+                    } => {
+                        let pk = public_key(key)?;
+                        Some(parse_ciphertext(&pk, ciphertext, *encoding).map(|c| {
+                            let (zero, _) = pk.encrypt(&Uint::ZERO, &mut OsRng.unwrap_err());
+                            encode_bytes(&pk.ciphertext_to_bytes(&pk.ciphertext_add(&c, &zero)), *encoding)
+                        }))
+                    }
+                }
+            }
+
+            fn parse_plaintext(pk: &$public_key, s: &str) -> Result<$plaintext, CliError> {
+                let m = parse_integer(s)?;
+                match bool::from(pk.plaintext_is_valid(&m)) {
+                    true => Ok(m),
+                    false => Err(CliError::InvalidPlaintext),
+                }
+            }
+
+            fn parse_ciphertext(
+                pk: &$public_key,
+                s: &str,
+                encoding: CiphertextEncoding,
+            ) -> Result<NonZero<$ciphertext>, CliError> {
+                let bytes = decode_bytes(s, encoding)?;
+                Option::from(pk.ciphertext_from_bytes(&bytes)).ok_or(CliError::InvalidCiphertext)
+            }
+        }
+    };
+}
+
+impl_commands!(paillier2048, PaillierSecretKey2048, PaillierPublicKey2048, U2048, U4096);
+impl_commands!(paillier4096, PaillierSecretKey4096, PaillierPublicKey4096, U4096, U8192);
+
+fn run(cli: Cli) -> Result<String, CliError> {
+    let key_path = match &cli.command {
+        Command::Keygen { bits, format } => {
+            return match bits {
+                4096 => paillier4096::keygen(*format),
+                _ => paillier2048::keygen(*format),
+            };
+        }
+        Command::Pubkey { key, .. }
+        | Command::Encrypt { key, .. }
+        | Command::Decrypt { key, .. }
+        | Command::Open { key, .. }
+        | Command::Add { key, .. }
+        | Command::MulScalar { key, .. }
+        | Command::Rerandomize { key, .. } => key,
+    };
+    let key = std::fs::read_to_string(key_path)?;
+
+    // smaller key types reject larger keys, so the first size that accepts the key is its actual size
+    paillier2048::run(&cli.command, &key)
+        .or_else(|| paillier4096::run(&cli.command, &key))
+        .unwrap_or(Err(CliError::InvalidKey))
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(output) => {
+            println!("{}", output.trim_end());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use crate::pk::PublicKey;
+use crate::sk::SecretKey;
+use crate::traits::{DecryptionKey, EncryptionKey, HomomorphicKey, Key};
+use crate::utils::shuffle;
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, RandomBits, Split, Uint};
+use rand_core::CryptoRng;
+use std::fmt;
+
+const STATISTICAL_SECURITY_BITS: u32 = 128;
+
+// bitwise comparison with paillier blinding (Veugen), for x, y < 2^bits the evaluator computes
+// z = 2^bits + x - y, whose bit at position `bits` is [x >= y], and sends z + r to the decryptor for a statistical
+// mask r; the decryptor returns d = z + r split into Enc(d div 2^bits) and the encrypted bits of d mod 2^bits, then
+// [x < y] = 1 - (d div 2^bits) + (r div 2^bits) + [d mod 2^bits < r mod 2^bits]; the last comparison runs over the
+// encrypted bits with a DGK style zero test, blinded by random scalars, shuffled and flipped by a random sign
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ComparisonError {
+    BoundTooLarge,
+    InvalidCiphertext,
+    UnexpectedMessage,
+}
+
+impl fmt::Display for ComparisonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComparisonError::BoundTooLarge => write!(f, "bit length bound does not fit the key"),
+            ComparisonError::InvalidCiphertext => write!(f, "ciphertext is not valid for the key"),
+            ComparisonError::UnexpectedMessage => write!(f, "message does not match the protocol state"),
+        }
+    }
+}
+
+impl std::error::Error for ComparisonError {}
+
+#[derive(Debug, Copy, Clone)]
+pub struct MaskedDifference<const D: usize> {
+    pub c: NonZero<Uint<D>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MaskedBits<const D: usize> {
+    pub high: NonZero<Uint<D>>,
+    pub bits: Vec<NonZero<Uint<D>>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BlindedTests<const D: usize> {
+    pub tests: Vec<NonZero<Uint<D>>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ZeroTestResult<const D: usize> {
+    pub c: NonZero<Uint<D>>,
+}
+
+#[derive(Clone)]
+pub struct ComparisonEvaluator<const S: usize, const D: usize> {
+    key: PublicKey<S, D>,
+    bits: u32,
+    mask: Uint<S>,
+    negated: bool,
+    high: Option<NonZero<Uint<D>>>,
+}
+
+// the mask and the sign flip would reveal the comparison result to whoever sees the decryptor's view
+impl<const S: usize, const D: usize> fmt::Debug for ComparisonEvaluator<S, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ComparisonEvaluator")
+            .field("key", &self.key)
+            .field("bits", &self.bits)
+            .field("high", &self.high)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ComparisonDecryptor<'a, const H: usize, const S: usize, const D: usize> {
+    sk: &'a SecretKey<H, S, D>,
+    bits: u32,
+}
+
+pub(crate) fn fits<const S: usize>(n: &Odd<Uint<S>>, bits: u32) -> bool {
+    // z + r < 2^(bits + kappa + 2) must not wrap around n
+    bits > 0 && n.bits() > bits + STATISTICAL_SECURITY_BITS + 2
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize>
+    ComparisonEvaluator<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    // x and y are encryptions of integers below 2^bits
+    pub fn new<R: CryptoRng + ?Sized>(
+        key: PublicKey<S, D>,
+        x: &NonZero<Uint<D>>,
+        y: &NonZero<Uint<D>>,
+        bits: u32,
+        rng: &mut R,
+    ) -> Result<(Self, MaskedDifference<D>), ComparisonError> {
+        if !fits(&key.n, bits) {
+            return Err(ComparisonError::BoundTooLarge);
+        }
+        if !bool::from(key.ciphertext_is_valid(x) & key.ciphertext_is_valid(y)) {
+            return Err(ComparisonError::InvalidCiphertext);
+        }
+
+        let mask = Uint::<S>::random_bits(rng, bits + 1 + STATISTICAL_SECURITY_BITS);
+        let offset = Uint::<S>::ONE.shl_vartime(bits).wrapping_add(&mask);
+        let z = key.ciphertext_add_plain(&key.ciphertext_sub(x, y), &offset);
+        let c = key.ciphertext_add(&z, &key.encrypt(&Uint::ZERO, rng).0);
+
+        let evaluator = ComparisonEvaluator {
+            key,
+            bits,
+            mask,
+            negated: rng.next_u32() & 1 == 1,
+            high: None,
+        };
+        Ok((evaluator, MaskedDifference { c }))
+    }
+
+    pub fn blind<R: CryptoRng + ?Sized>(
+        &mut self,
+        message: &MaskedBits<D>,
+        rng: &mut R,
+    ) -> Result<BlindedTests<D>, ComparisonError> {
+        if self.high.is_some() || message.bits.len() != self.bits as usize {
+            return Err(ComparisonError::UnexpectedMessage);
+        }
+        let key = &self.key;
+        if !message
+            .bits
+            .iter()
+            .chain([&message.high])
+            .all(|c| key.ciphertext_is_valid(c).into())
+        {
+            return Err(ComparisonError::InvalidCiphertext);
+        }
+
+        // compare d' = 2 (d mod 2^bits) + 1 with r' = 2 (r mod 2^bits), they are never equal and
+        // [d' < r'] = [d mod 2^bits < r mod 2^bits]
+        let one = key.encrypt_with_nonce(&Uint::ONE, &NonZero::ONE);
+        let d_bits: Vec<_> = [one].iter().chain(&message.bits).copied().collect();
+        let r_bits: Vec<bool> = (0..=self.bits).map(|i| i > 0 && self.mask.bit_vartime(i - 1)).collect();
+
+        // c_i = d_i - r_i + s + 3 sum_{j > i} (d_j xor r_j) is zero for exactly one i if s = 1 and d' < r' or
+        // s = -1 and d' > r', and non zero everywhere otherwise
+        let mut tests = Vec::with_capacity(d_bits.len());
+        let mut xor_sum = NonZero::<Uint<D>>::ONE;
+        for (d_i, &r_i) in d_bits.iter().zip(&r_bits).rev() {
+            let mut c = key.ciphertext_add(d_i, &key.ciphertext_mul_scalar(&xor_sum, &Uint::from_u8(3)));
+            c = match (self.negated, r_i) {
+                (false, false) => key.ciphertext_add_plain(&c, &Uint::ONE),
+                (false, true) => c,
+                (true, false) => key.ciphertext_sub_plain(&c, &Uint::ONE),
+                (true, true) => key.ciphertext_sub_plain(&c, &Uint::from_u8(2)),
+            };
+
+            let mut scalar = Uint::ZERO;
+            while scalar == Uint::ZERO {
+                scalar = key.random_plaintext(rng);
+            }
+            let blinded = key.ciphertext_mul_scalar(&c, &scalar);
+            tests.push(key.ciphertext_add(&blinded, &key.encrypt(&Uint::ZERO, rng).0));
+
+            let xor = if r_i {
+                key.ciphertext_add_plain(&key.ciphertext_neg(d_i), &Uint::ONE)
+            } else {
+                *d_i
+            };
+            xor_sum = key.ciphertext_add(&xor_sum, &xor);
+        }
+        shuffle(&mut tests, rng);
+
+        self.high = Some(message.high);
+        Ok(BlindedTests { tests })
+    }
+
+    pub fn finish(self, message: &ZeroTestResult<D>) -> Result<NonZero<Uint<D>>, ComparisonError> {
+        let high = self.high.ok_or(ComparisonError::UnexpectedMessage)?;
+        let key = &self.key;
+        if !bool::from(key.ciphertext_is_valid(&message.c)) {
+            return Err(ComparisonError::InvalidCiphertext);
+        }
+
+        // t = [d mod 2^bits < r mod 2^bits]
+        let t = if self.negated {
+            key.ciphertext_add_plain(&key.ciphertext_neg(&message.c), &Uint::ONE)
+        } else {
+            message.c
+        };
+
+        // [x < y] = 1 - (d div 2^bits) + (r div 2^bits) + t
+        let mask_high = self.mask.shr_vartime(self.bits);
+        let result = key.ciphertext_add(&key.ciphertext_neg(&high), &t);
+        let result = key.ciphertext_add_plain(&result, &mask_high.wrapping_add(&Uint::ONE));
+        Ok(result)
+    }
+}
+
+impl<
+    'a,
+    const H: usize,
+    const H_UNSAT: usize,
+    const S: usize,
+    const S_UNSAT: usize,
+    const D: usize,
+    const D_UNSAT: usize,
+    const Q: usize,
+> ComparisonDecryptor<'a, H, S, D>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn new(sk: &'a SecretKey<H, S, D>, bits: u32) -> Result<Self, ComparisonError> {
+        if !fits(&sk.pk.n, bits) {
+            return Err(ComparisonError::BoundTooLarge);
+        }
+
+        Ok(ComparisonDecryptor { sk, bits })
+    }
+
+    pub fn decompose<R: CryptoRng + ?Sized>(
+        &self,
+        message: &MaskedDifference<D>,
+        rng: &mut R,
+    ) -> Result<MaskedBits<D>, ComparisonError> {
+        let pk = &self.sk.pk;
+        let d = Option::<Uint<S>>::from(self.sk.try_decrypt(&message.c)).ok_or(ComparisonError::InvalidCiphertext)?;
+
+        let high = pk.encrypt(&d.shr_vartime(self.bits), rng).0;
+        let bits = (0..self.bits)
+            .map(|i| pk.encrypt(&Uint::from_u8(d.bit_vartime(i) as u8), rng).0)
+            .collect();
+        Ok(MaskedBits { high, bits })
+    }
+
+    pub fn test_zeros<R: CryptoRng + ?Sized>(
+        &self,
+        message: &BlindedTests<D>,
+        rng: &mut R,
+    ) -> Result<ZeroTestResult<D>, ComparisonError> {
+        if message.tests.len() != self.bits as usize + 1 {
+            return Err(ComparisonError::UnexpectedMessage);
+        }
+
+        let mut has_zero = false;
+        for c in &message.tests {
+            let m = Option::<Uint<S>>::from(self.sk.try_decrypt(c)).ok_or(ComparisonError::InvalidCiphertext)?;
+            has_zero |= m == Uint::ZERO;
+        }
+
+        let c = self.sk.pk.encrypt(&Uint::from_u8(has_zero as u8), rng).0;
+        Ok(ZeroTestResult { c })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::comparison::{ComparisonDecryptor, ComparisonError, ComparisonEvaluator};
+    use crate::sk::SecretKey;
+    use crate::{DecryptionKey, EncryptionKey, KeyGenerator};
+    use crypto_bigint::{U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+
+    #[test]
+    fn should_compare_encrypted_integers() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let decryptor = ComparisonDecryptor::new(&sk, 8).unwrap();
+
+        for (x, y) in [
+            (3u64, 5u64),
+            (5, 3),
+            (7, 7),
+            (0, 255),
+            (255, 0),
+            (0, 0),
+            (255, 255),
+            (128, 127),
+        ] {
+            let cx = pk.encrypt(&U512::from_u64(x), &mut rng).0;
+            let cy = pk.encrypt(&U512::from_u64(y), &mut rng).0;
+
+            let (mut evaluator, masked) = ComparisonEvaluator::new(pk, &cx, &cy, 8, &mut rng).unwrap();
+            let bits = decryptor.decompose(&masked, &mut rng).unwrap();
+            let tests = evaluator.blind(&bits, &mut rng).unwrap();
+            let result = decryptor.test_zeros(&tests, &mut rng).unwrap();
+            let c = evaluator.finish(&result).unwrap();
+
+            assert_eq!(sk.decrypt(&c), U512::from_u8((x < y) as u8), "{x} < {y}");
+        }
+    }
+
+    #[test]
+    fn should_reject_out_of_order_messages() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let cx = pk.encrypt(&U512::ONE, &mut rng).0;
+
+        assert_eq!(
+            ComparisonEvaluator::new(pk, &cx, &cx, 400, &mut rng).unwrap_err(),
+            ComparisonError::BoundTooLarge
+        );
+
+        let decryptor = ComparisonDecryptor::new(&sk, 8).unwrap();
+        let (evaluator, masked) = ComparisonEvaluator::new(pk, &cx, &cx, 8, &mut rng).unwrap();
+        let debug = format!("{evaluator:?}");
+        assert!(!debug.contains("mask") && !debug.contains("negated"));
+        let mut bits = decryptor.decompose(&masked, &mut rng).unwrap();
+        bits.bits.pop();
+        let mut stale = evaluator.clone();
+        assert_eq!(
+            stale.blind(&bits, &mut rng).unwrap_err(),
+            ComparisonError::UnexpectedMessage
+        );
+
+        let result = crate::comparison::ZeroTestResult { c: cx };
+        assert_eq!(
+            evaluator.finish(&result).unwrap_err(),
+            ComparisonError::UnexpectedMessage
+        );
+    }
+}
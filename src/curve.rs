@@ -0,0 +1,66 @@
+use crate::utils::{uint_from_be_bytes, uint_to_be_bytes};
+use crate::zk::Transcript;
+use crypto_bigint::{NonZero, Odd, Uint};
+use elliptic_curve::group::Group;
+use elliptic_curve::point::AffineCoordinates;
+use elliptic_curve::{CurveArithmetic, Field, FieldBytes, PrimeField};
+
+// ell is the curve order length and epsilon the slack of the masks hiding the witnesses
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct CurveRanges {
+    pub(crate) ell: u32,
+    pub(crate) epsilon: u32,
+}
+
+impl CurveRanges {
+    pub(crate) fn new<C: CurveArithmetic, const S: usize>() -> Self {
+        let ell = curve_order::<C, S>().bits();
+
+        CurveRanges { ell, epsilon: 2 * ell }
+    }
+
+    // a response z < 2^(bits + epsilon + 1) has to be a valid plaintext
+    pub(crate) fn fits<const S: usize>(&self, n: &Odd<Uint<S>>, bits: u32) -> bool {
+        n.bits() > bits + self.epsilon + 1
+    }
+}
+
+// field bytes of the supported curves are big endian, so scalars map to integers by plain byte copying
+pub(crate) fn curve_order<C: CurveArithmetic, const L: usize>() -> NonZero<Uint<L>> {
+    let max = scalar_to_uint::<C, L>(&-C::Scalar::ONE);
+    max.wrapping_add(&Uint::ONE)
+        .to_nz()
+        .expect("curve order fits into the integer")
+}
+
+pub(crate) fn scalar_to_uint<C: CurveArithmetic, const L: usize>(x: &C::Scalar) -> Uint<L> {
+    uint_from_be_bytes(x.to_repr().as_ref()).expect("scalar fits into the integer")
+}
+
+pub(crate) fn uint_to_scalar<C: CurveArithmetic, const L: usize>(x: &Uint<L>) -> C::Scalar {
+    let reduced = x.rem(&curve_order::<C, L>());
+    let bytes = uint_to_be_bytes(&reduced);
+
+    let mut repr = FieldBytes::<C>::default();
+    let offset = bytes.len() - repr.len();
+    repr.copy_from_slice(&bytes[offset..]);
+    C::Scalar::from_repr(repr).expect("reduced integer is a canonical scalar")
+}
+
+pub(crate) fn append_point<C: CurveArithmetic>(
+    transcript: &mut Transcript,
+    label: &'static [u8],
+    point: &C::ProjectivePoint,
+) {
+    let affine: C::AffinePoint = (*point).into();
+    let mut bytes = vec![point.is_identity().unwrap_u8(), affine.y_is_odd().unwrap_u8()];
+    bytes.extend_from_slice(affine.x().as_ref());
+    transcript.append_bytes(label, &bytes);
+}
+
+#[cfg(test)]
+pub(crate) fn random_scalar<C: CurveArithmetic, R: rand_core::CryptoRng + ?Sized>(rng: &mut R) -> C::Scalar {
+    use crypto_bigint::{RandomMod, U512};
+
+    uint_to_scalar::<C, { U512::LIMBS }>(&U512::random_mod(rng, &curve_order::<C, { U512::LIMBS }>()))
+}
@@ -0,0 +1,5 @@
+mod pk;
+mod sk;
+
+pub use crate::jl::pk::PublicKey;
+pub use crate::jl::sk::SecretKey;
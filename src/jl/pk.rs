@@ -0,0 +1,244 @@
+use crate::traits::{EncryptionKey, HomomorphicKey, Key};
+use crypto_bigint::modular::{MontyForm, MontyParams, SafeGcdInverter};
+use crypto_bigint::{NonZero, Odd, PrecomputeInverter, RandomMod, Uint};
+use rand_core::CryptoRng;
+use subtle::{Choice, ConstantTimeEq, ConstantTimeLess};
+
+// Joye-Libert over n = pq with p = 1 mod 2^k and y a non residue modulo p and q, c = y^m x^(2^k) mod n;
+// plaintexts are integers modulo 2^k, so homomorphic sums silently wrap around at 2^k
+#[derive(Debug, Copy, Clone)]
+pub struct PublicKey<const S: usize> {
+    pub(crate) n: Odd<Uint<S>>,
+    pub(crate) y: Uint<S>,
+    pub(crate) k: u32,
+    pub(crate) n_monty_params: MontyParams<S>,
+}
+
+impl<const S: usize> PublicKey<S> {
+    pub fn from_parts_unchecked(n: Odd<Uint<S>>, y: Uint<S>, k: u32) -> Self {
+        let n_monty_params = MontyParams::new_vartime(n);
+
+        PublicKey {
+            n,
+            y,
+            k,
+            n_monty_params,
+        }
+    }
+
+    pub fn n(&self) -> &Odd<Uint<S>> {
+        &self.n
+    }
+
+    pub fn y(&self) -> &Uint<S> {
+        &self.y
+    }
+
+    pub fn plaintext_bits(&self) -> u32 {
+        self.k
+    }
+
+    fn y_to(&self, m: &Uint<S>) -> Uint<S> {
+        MontyForm::new(&self.y, self.n_monty_params).pow(m).retrieve()
+    }
+
+    fn mul_mod_n(&self, x: &Uint<S>, z: &Uint<S>) -> NonZero<Uint<S>> {
+        let product = MontyForm::new(x, self.n_monty_params) * MontyForm::new(z, self.n_monty_params);
+        product.retrieve().to_nz().expect("units are non zero")
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize> PublicKey<S>
+where
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+{
+    pub fn random_plaintext<R: CryptoRng + ?Sized>(&self, rng: &mut R) -> Uint<S> {
+        Uint::random_mod(rng, &Uint::ONE.shl_vartime(self.k).to_nz().expect("bound is non zero"))
+    }
+
+    pub fn random_nonce<R: CryptoRng + ?Sized>(&self, rng: &mut R) -> NonZero<Uint<S>> {
+        let mut x = Uint::ZERO;
+        while x.gcd(self.n.as_ref()) != Uint::ONE {
+            x = Uint::random_mod(rng, self.n.as_nz_ref());
+        }
+
+        x.to_nz().expect("x is a unit")
+    }
+
+    fn inv_mod_n(&self, x: &Uint<S>) -> NonZero<Uint<S>> {
+        x.inv_odd_mod(&self.n)
+            .expect("units are invertible")
+            .to_nz()
+            .expect("units are non zero")
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize> Key<Uint<S>> for PublicKey<S>
+where
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+{
+    type Ciphertext = NonZero<Uint<S>>;
+    type Nonce = NonZero<Uint<S>>;
+
+    fn plaintext_is_valid(&self, m: &Uint<S>) -> Choice {
+        m.ct_lt(&Uint::ONE.shl_vartime(self.k))
+    }
+
+    fn plaintext_eq(&self, ml: &Uint<S>, mr: &Uint<S>) -> Choice {
+        self.plaintext_is_valid(ml) & self.plaintext_is_valid(mr) & ml.ct_eq(mr)
+    }
+
+    fn ciphertext_is_valid(&self, c: &Self::Ciphertext) -> Choice {
+        c.ct_lt(&self.n) & c.gcd(&self.n).ct_eq(&Uint::ONE)
+    }
+
+    fn ciphertext_eq(&self, cl: &Self::Ciphertext, cr: &Self::Ciphertext) -> Choice {
+        self.ciphertext_is_valid(cl) & self.ciphertext_is_valid(cr) & cl.ct_eq(cr)
+    }
+
+    fn nonce_is_valid(&self, x: &Self::Nonce) -> Choice {
+        x.ct_lt(&self.n) & x.gcd(&self.n).ct_eq(&Uint::ONE)
+    }
+
+    fn nonce_eq(&self, xl: &Self::Nonce, xr: &Self::Nonce) -> Choice {
+        self.nonce_is_valid(xl) & self.nonce_is_valid(xr) & xl.ct_eq(xr)
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize> EncryptionKey<Uint<S>> for PublicKey<S>
+where
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+{
+    fn encrypt_with_nonce(&self, m: &Uint<S>, x: &Self::Nonce) -> Self::Ciphertext {
+        // x^(2^k) by k squarings
+        let mut x_to_2k = MontyForm::new(x, self.n_monty_params);
+        for _ in 0..self.k {
+            x_to_2k = x_to_2k.square();
+        }
+
+        self.mul_mod_n(&self.y_to(m), &x_to_2k.retrieve())
+    }
+
+    fn encrypt<R: CryptoRng + ?Sized>(&self, m: &Uint<S>, rng: &mut R) -> (Self::Ciphertext, Self::Nonce) {
+        let x = self.random_nonce(rng);
+        let c = self.encrypt_with_nonce(m, &x);
+        (c, x)
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize> HomomorphicKey<Uint<S>> for PublicKey<S>
+where
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+{
+    type Scalar = Uint<S>;
+
+    fn scalar_is_valid(&self, _s: &Self::Scalar) -> Choice {
+        // scalars act on plaintexts modulo 2^k, larger ones are simply reduced
+        Choice::from(1)
+    }
+
+    fn scalar_eq(&self, sl: &Self::Scalar, sr: &Self::Scalar) -> Choice {
+        sl.ct_eq(sr)
+    }
+
+    fn ciphertext_add(&self, cl: &Self::Ciphertext, cr: &Self::Ciphertext) -> Self::Ciphertext {
+        self.mul_mod_n(cl, cr)
+    }
+
+    fn ciphertext_add_plain(&self, c: &Self::Ciphertext, m: &Uint<S>) -> Self::Ciphertext {
+        self.mul_mod_n(c, &self.y_to(m))
+    }
+
+    fn ciphertext_sub(&self, cl: &Self::Ciphertext, cr: &Self::Ciphertext) -> Self::Ciphertext {
+        self.mul_mod_n(cl, &self.inv_mod_n(cr))
+    }
+
+    fn ciphertext_sub_plain(&self, c: &Self::Ciphertext, m: &Uint<S>) -> Self::Ciphertext {
+        self.mul_mod_n(c, &self.inv_mod_n(&self.y_to(m)))
+    }
+
+    fn ciphertext_neg(&self, c: &Self::Ciphertext) -> Self::Ciphertext {
+        self.inv_mod_n(c)
+    }
+
+    fn ciphertext_mul_scalar(&self, c: &Self::Ciphertext, s: &Self::Scalar) -> Self::Ciphertext {
+        let c_monty_form = MontyForm::new(c, self.n_monty_params);
+        c_monty_form.pow(s).retrieve().to_nz().expect("c is non zero")
+    }
+
+    fn nonce_add(&self, xl: &Self::Nonce, xr: &Self::Nonce) -> Self::Nonce {
+        self.mul_mod_n(xl, xr)
+    }
+
+    fn nonce_sub(&self, xl: &Self::Nonce, xr: &Self::Nonce) -> Self::Nonce {
+        self.mul_mod_n(xl, &self.inv_mod_n(xr))
+    }
+
+    fn nonce_neg(&self, x: &Self::Nonce) -> Self::Nonce {
+        self.inv_mod_n(x)
+    }
+
+    fn nonce_mul_scalar(&self, x: &Self::Nonce, s: &Self::Scalar) -> Self::Nonce {
+        let x_monty_form = MontyForm::new(x, self.n_monty_params);
+        x_monty_form.pow(s).retrieve().to_nz().expect("x is non zero")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::jl::SecretKey;
+    use crate::{DecryptionKey, EncryptionKey, HomomorphicKey};
+    use crypto_bigint::{NonZero, U256, U512};
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }>;
+
+    #[test]
+    fn should_homomorphic_add_and_wrap_around() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let sk = SmallSecretKey::random_with_plaintext_bits(64, &mut rng);
+        let pk = sk.as_public_key();
+        let modulus = NonZero::new(U512::ONE.shl_vartime(64)).unwrap();
+
+        let m1 = pk.random_plaintext(&mut rng);
+        let m2 = pk.random_plaintext(&mut rng);
+        let (c1, x1) = pk.encrypt(&m1, &mut rng);
+        let (c2, x2) = pk.encrypt(&m2, &mut rng);
+
+        let c = pk.ciphertext_add(&c1, &c2);
+        assert_eq!(c, pk.encrypt_with_nonce(&m1.wrapping_add(&m2), &pk.nonce_add(&x1, &x2)));
+        assert_eq!(sk.decrypt(&c), m1.add_mod(&m2, &modulus));
+
+        let max = modulus.wrapping_sub(&U512::ONE);
+        let (c_max, _) = pk.encrypt(&max, &mut rng);
+        assert_eq!(sk.decrypt(&pk.ciphertext_add_plain(&c_max, &U512::ONE)), U512::ZERO);
+    }
+
+    #[test]
+    fn should_homomorphic_sub_neg_and_mul_scalar() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let sk = SmallSecretKey::random_with_plaintext_bits(64, &mut rng);
+        let pk = sk.as_public_key();
+        let modulus = NonZero::new(U512::ONE.shl_vartime(64)).unwrap();
+
+        let m1 = pk.random_plaintext(&mut rng);
+        let m2 = pk.random_plaintext(&mut rng);
+        let (c1, x1) = pk.encrypt(&m1, &mut rng);
+        let (c2, _) = pk.encrypt(&m2, &mut rng);
+
+        assert_eq!(sk.decrypt(&pk.ciphertext_sub(&c1, &c2)), m1.sub_mod(&m2, &modulus));
+        assert_eq!(
+            sk.decrypt(&pk.ciphertext_sub_plain(&c1, &m2)),
+            m1.sub_mod(&m2, &modulus)
+        );
+        assert_eq!(sk.decrypt(&pk.ciphertext_neg(&c1)), m1.neg_mod(&modulus));
+
+        let c = pk.ciphertext_mul_scalar(&c1, &m2);
+        assert_eq!(
+            c,
+            pk.encrypt_with_nonce(&m1.wrapping_mul(&m2), &pk.nonce_mul_scalar(&x1, &m2))
+        );
+        assert_eq!(sk.decrypt(&c), m1.wrapping_mul(&m2).rem(&modulus));
+    }
+}
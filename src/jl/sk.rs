@@ -0,0 +1,241 @@
+use crate::jl::pk::PublicKey;
+use crate::traits::{DecryptionKey, Key, KeyGenerator};
+use crypto_bigint::modular::{MontyForm, MontyParams, SafeGcdInverter};
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, RandomBits, RandomMod, Split, Uint};
+use crypto_primes::RandomPrimeWithRng;
+use rand_core::CryptoRng;
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+
+// plaintext length used by KeyGenerator::random, enough for 64 bit counters
+const DEFAULT_PLAINTEXT_BITS: u32 = 64;
+
+#[derive(Debug, Copy, Clone)]
+pub struct SecretKey<const H: usize, const S: usize> {
+    pub(crate) pk: PublicKey<S>,
+    pub(crate) p: Odd<Uint<H>>,
+    pub(crate) p_monty_params: MontyParams<H>,
+    // (p - 1) / 2^k
+    pub(crate) e: Uint<H>,
+    // y^-((p - 1) / 2^k) mod p
+    pub(crate) d: Uint<H>,
+}
+
+impl<const H: usize, const S: usize, const S_UNSAT: usize> SecretKey<H, S>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Uint<S>: Split<Output = Uint<H>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+{
+    pub fn from_primes<R: CryptoRng + ?Sized>(p: Odd<Uint<H>>, q: Odd<Uint<H>>, k: u32, rng: &mut R) -> Self {
+        Self::check_plaintext_bits(k);
+        if p == q
+            || p.bits() != Uint::<H>::BITS
+            || q.bits() != Uint::<H>::BITS
+            || !p.as_ref().is_prime_with_rng(rng)
+            || !q.as_ref().is_prime_with_rng(rng)
+        {
+            panic!("p and q must be prime and have the same length");
+        }
+        if p.as_ref().wrapping_sub(&Uint::ONE).trailing_zeros() < k || q.as_ref().as_limbs()[0].0 & 3 != 3 {
+            panic!("p must be 1 modulo 2^k and q must be 3 modulo 4");
+        }
+
+        Self::from_primes_unchecked(p, q, k, rng)
+    }
+
+    // y is drawn at random until it is a quadratic non residue modulo both p and q
+    pub fn from_primes_unchecked<R: CryptoRng + ?Sized>(p: Odd<Uint<H>>, q: Odd<Uint<H>>, k: u32, rng: &mut R) -> Self {
+        let n = p.widening_mul(&q).to_odd().expect("n is a product of odd primes");
+        let pm1 = p.wrapping_sub(&Uint::ONE);
+        let p_monty_params = MontyParams::new(p);
+        let q_monty_params = MontyParams::new(q);
+        let e = pm1.shr_vartime(k);
+
+        loop {
+            let y = Uint::<S>::random_mod(rng, n.as_nz_ref());
+            let y_mod_p = reduce(&y, &p);
+            let y_mod_q = reduce(&y, &q);
+            if !is_non_residue(&y_mod_p, &p_monty_params) || !is_non_residue(&y_mod_q, &q_monty_params) {
+                continue;
+            }
+
+            // y^(p - 1) = 1 mod p, so y^-e = y^(p - 1 - e)
+            let d = MontyForm::new(&y_mod_p, p_monty_params)
+                .pow(&pm1.wrapping_sub(&e))
+                .retrieve();
+            return SecretKey {
+                pk: PublicKey::from_parts_unchecked(n, y, k),
+                p,
+                p_monty_params,
+                e,
+                d,
+            };
+        }
+    }
+
+    pub fn random_with_plaintext_bits<R: CryptoRng + ?Sized>(k: u32, rng: &mut R) -> Self {
+        Self::check_plaintext_bits(k);
+
+        // p = 2^k p' + 1 with p' of exactly H::BITS - k bits
+        let top = Uint::<H>::ONE.shl_vartime(Uint::<H>::BITS - k - 1);
+        let p = loop {
+            let p = Uint::<H>::random_bits(rng, Uint::<H>::BITS - k)
+                .bitor(&top)
+                .shl_vartime(k)
+                .bitor(&Uint::ONE);
+            if p.is_prime_with_rng(rng) {
+                break p;
+            }
+        };
+        let q = loop {
+            let q = Uint::<H>::generate_prime_with_rng(rng, Uint::<H>::BITS);
+            if q.as_limbs()[0].0 & 3 == 3 && q != p {
+                break q;
+            }
+        };
+
+        Self::from_primes_unchecked(p.to_odd().unwrap(), q.to_odd().unwrap(), k, rng)
+    }
+
+    pub fn as_public_key(&self) -> PublicKey<S> {
+        self.pk.to_owned()
+    }
+
+    fn check_plaintext_bits(k: u32) {
+        // factoring n = pq gets easier as p - 1 gains a known factor 2^k, keep it to a quarter of the prime
+        if k == 0 || k > Uint::<H>::BITS / 4 {
+            panic!("plaintext length must be between 1 and a quarter of the prime length");
+        }
+    }
+}
+
+fn reduce<const H: usize, const S: usize>(x: &Uint<S>, p: &Odd<Uint<H>>) -> Uint<H> {
+    x.rem(&p.resize::<S>().to_nz().expect("p is non zero")).resize()
+}
+
+fn is_non_residue<const H: usize>(x: &Uint<H>, p_monty_params: &MontyParams<H>) -> bool {
+    // Euler's criterion, x^((p - 1) / 2) = -1 mod p
+    let pm1 = p_monty_params.modulus().wrapping_sub(&Uint::ONE);
+    MontyForm::new(x, *p_monty_params).pow(&pm1.shr_vartime(1)).retrieve() == pm1
+}
+
+impl<const H: usize, const S: usize, const S_UNSAT: usize> Key<Uint<S>> for SecretKey<H, S>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Uint<S>: Split<Output = Uint<H>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+{
+    type Ciphertext = NonZero<Uint<S>>;
+    type Nonce = NonZero<Uint<S>>;
+
+    fn plaintext_is_valid(&self, m: &Uint<S>) -> subtle::Choice {
+        self.pk.plaintext_is_valid(m)
+    }
+
+    fn plaintext_eq(&self, ml: &Uint<S>, mr: &Uint<S>) -> subtle::Choice {
+        self.pk.plaintext_eq(ml, mr)
+    }
+
+    fn ciphertext_is_valid(&self, c: &Self::Ciphertext) -> subtle::Choice {
+        self.pk.ciphertext_is_valid(c)
+    }
+
+    fn ciphertext_eq(&self, cl: &Self::Ciphertext, cr: &Self::Ciphertext) -> subtle::Choice {
+        self.pk.ciphertext_eq(cl, cr)
+    }
+
+    fn nonce_is_valid(&self, x: &Self::Nonce) -> subtle::Choice {
+        self.pk.nonce_is_valid(x)
+    }
+
+    fn nonce_eq(&self, xl: &Self::Nonce, xr: &Self::Nonce) -> subtle::Choice {
+        self.pk.nonce_eq(xl, xr)
+    }
+}
+
+impl<const H: usize, const S: usize, const S_UNSAT: usize> DecryptionKey<Uint<S>> for SecretKey<H, S>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Uint<S>: Split<Output = Uint<H>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+{
+    fn decrypt(&self, c: &Self::Ciphertext) -> Uint<S> {
+        // z = c^((p - 1) / 2^k) = (y^e)^m mod p, where y^e has order 2^k; bit j of m is read off
+        // z^(2^(k - 1 - j)) = (-1)^m_j once the lower bits have been divided out
+        let k = self.pk.k;
+        let mut z = MontyForm::new(&reduce(c, &self.p), self.p_monty_params).pow(&self.e);
+        let mut d = MontyForm::new(&self.d, self.p_monty_params);
+        let mut m = Uint::<S>::ZERO;
+        for j in 0..k {
+            let mut t = z;
+            for _ in 0..k - 1 - j {
+                t = t.square();
+            }
+            let bit = !t.retrieve().ct_eq(&Uint::ONE);
+            m = m.bitor(&Uint::from_u8(bit.unwrap_u8()).shl_vartime(j));
+            z = MontyForm::conditional_select(&z, &(z * d), bit);
+            d = d.square();
+        }
+
+        m
+    }
+}
+
+impl<const H: usize, const S: usize, const S_UNSAT: usize> KeyGenerator<Uint<S>> for SecretKey<H, S>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Uint<S>: Split<Output = Uint<H>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+{
+    type EncryptionKey = PublicKey<S>;
+
+    fn random<R: CryptoRng + ?Sized>(rng: &mut R) -> (Self, Self::EncryptionKey) {
+        let sk = Self::random_with_plaintext_bits(DEFAULT_PLAINTEXT_BITS, rng);
+        let pk = sk.pk;
+        (sk, pk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::jl::SecretKey;
+    use crate::{DecryptionKey, EncryptionKey, JoyeLibertSecretKey2048, Key, KeyGenerator};
+    use crypto_bigint::{NonZero, U256, U512, U2048};
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }>;
+
+    #[test]
+    fn should_generate_random_key() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = JoyeLibertSecretKey2048::random(&mut rng);
+
+        assert_eq!(pk.plaintext_bits(), 64);
+        assert!(pk.n().bits() > U2048::BITS - 2);
+        assert!(sk.p.as_ref().wrapping_sub(&U2048::ONE.resize()).trailing_zeros() >= 64);
+
+        let max = U2048::ONE.shl_vartime(64).wrapping_sub(&U2048::ONE);
+        for m in [U2048::ZERO, max, pk.random_plaintext(&mut rng)] {
+            let (c, _) = pk.encrypt(&m, &mut rng);
+            assert_eq!(sk.decrypt(&c), m);
+        }
+    }
+
+    #[test]
+    fn should_decrypt_short_plaintexts_and_reject_invalid_ciphertext() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let sk = SmallSecretKey::random_with_plaintext_bits(16, &mut rng);
+        let pk = sk.as_public_key();
+
+        assert!(bool::from(pk.plaintext_is_valid(&U512::from_u32(0xffff))));
+        assert!(!bool::from(pk.plaintext_is_valid(&U512::from_u32(0x10000))));
+
+        let m = pk.random_plaintext(&mut rng);
+        let (c, _) = pk.encrypt(&m, &mut rng);
+        assert_eq!(sk.try_decrypt(&c).unwrap(), m);
+
+        let c = NonZero::new(*pk.n().as_ref()).unwrap();
+        assert!(bool::from(sk.try_decrypt(&c).is_none()));
+    }
+}
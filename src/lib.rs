@@ -1,19 +1,70 @@
-use crypto_bigint::{U1024, U1536, U2048, U3072, U4096, U6144, U8192};
+use crypto_bigint::{U512, U1024, U1536, U2048, U3072, U4096, U8192};
 
+pub mod aggregation;
+pub mod auction;
+pub mod bcp;
+pub mod comparison;
+#[cfg(feature = "curve")]
+mod curve;
+mod jl;
+#[cfg(feature = "mta")]
+pub mod mta;
+pub mod ot;
+mod ou;
+pub mod pir;
 mod pk;
+pub mod psi;
+pub mod ring_pedersen;
+pub mod secure_mul;
 mod sk;
 mod traits;
+pub mod truncation;
 mod utils;
+pub mod voting;
+pub mod zk;
 
+#[cfg(feature = "curve")]
+pub use elliptic_curve;
+#[cfg(feature = "k256")]
+pub use k256;
+#[cfg(feature = "p256")]
+pub use p256;
+#[cfg(feature = "unstable-pkcs8")]
+pub use pkcs8;
+
+pub use jl::PublicKey as JoyeLibertPublicKey;
+pub use jl::SecretKey as JoyeLibertSecretKey;
+pub use ou::PublicKey as OkamotoUchiyamaPublicKey;
+pub use ou::SecretKey as OkamotoUchiyamaSecretKey;
+#[cfg(feature = "unstable-pkcs8")]
+pub use pk::ALGORITHM_OID as PAILLIER_OID;
+#[cfg(feature = "json")]
+pub use pk::JsonError;
 pub use pk::PublicKey as PaillierPublicKey;
+pub use pk::{BatchError, FINGERPRINT_LEN};
+pub use pk::{ValidationPolicy, ValidationReport};
+pub use sk::KeyProperties as PaillierKeyProperties;
 pub use sk::SecretKey as PaillierSecretKey;
+pub use sk::SecretKeyBuilder as PaillierSecretKeyBuilder;
 pub use traits::{DecryptionKey, EncryptionKey, HomomorphicKey, Key, KeyGenerator, OpeningKey};
 
 pub type PaillierSecretKey2048 = PaillierSecretKey<{ U1024::LIMBS }, { U2048::LIMBS }, { U4096::LIMBS }>;
 pub type PaillierPublicKey2048 = PaillierPublicKey<{ U2048::LIMBS }, { U4096::LIMBS }>;
 
-pub type PaillierSecretKey3072 = PaillierSecretKey<{ U1536::LIMBS }, { U3072::LIMBS }, { U6144::LIMBS }>;
-pub type PaillierPublicKey3072 = PaillierPublicKey<{ U3072::LIMBS }, { U6144::LIMBS }>;
-
 pub type PaillierSecretKey4096 = PaillierSecretKey<{ U2048::LIMBS }, { U4096::LIMBS }, { U8192::LIMBS }>;
 pub type PaillierPublicKey4096 = PaillierPublicKey<{ U4096::LIMBS }, { U8192::LIMBS }>;
+
+pub type OkamotoUchiyamaSecretKey1536 = OkamotoUchiyamaSecretKey<{ U512::LIMBS }, { U1024::LIMBS }, { U2048::LIMBS }>;
+pub type OkamotoUchiyamaPublicKey1536 = OkamotoUchiyamaPublicKey<{ U512::LIMBS }, { U2048::LIMBS }>;
+
+pub type OkamotoUchiyamaSecretKey3072 = OkamotoUchiyamaSecretKey<{ U1024::LIMBS }, { U2048::LIMBS }, { U4096::LIMBS }>;
+pub type OkamotoUchiyamaPublicKey3072 = OkamotoUchiyamaPublicKey<{ U1024::LIMBS }, { U4096::LIMBS }>;
+
+pub type JoyeLibertSecretKey2048 = JoyeLibertSecretKey<{ U1024::LIMBS }, { U2048::LIMBS }>;
+pub type JoyeLibertPublicKey2048 = JoyeLibertPublicKey<{ U2048::LIMBS }>;
+
+pub type JoyeLibertSecretKey3072 = JoyeLibertSecretKey<{ U1536::LIMBS }, { U3072::LIMBS }>;
+pub type JoyeLibertPublicKey3072 = JoyeLibertPublicKey<{ U3072::LIMBS }>;
+
+pub type JoyeLibertSecretKey4096 = JoyeLibertSecretKey<{ U2048::LIMBS }, { U4096::LIMBS }>;
+pub type JoyeLibertPublicKey4096 = JoyeLibertPublicKey<{ U4096::LIMBS }>;
@@ -0,0 +1,269 @@
+use crate::curve::{CurveRanges, scalar_to_uint, uint_to_scalar};
+use crate::pk::PublicKey;
+use crate::ring_pedersen::RingPedersenParams;
+use crate::traits::{DecryptionKey, EncryptionKey, HomomorphicKey, Key};
+use crate::zk::{AffineProof, AffineStatement, AffineWitness, ell_prime};
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, RandomBits, Split, Uint};
+use elliptic_curve::CurveArithmetic;
+use elliptic_curve::ops::MulByGenerator;
+use rand_core::CryptoRng;
+use std::fmt;
+use std::marker::PhantomData;
+
+// multiplicative to additive share conversion: Alice holds a and the decryption key, Bob holds b, at the end
+// alpha + beta = a * b over the scalar field of the curve
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MtaError {
+    ModulusTooShort,
+    InvalidCiphertext,
+    MissingProof,
+    InvalidProof,
+}
+
+impl fmt::Display for MtaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MtaError::ModulusTooShort => write!(f, "paillier modulus is too short for the curve"),
+            MtaError::InvalidCiphertext => write!(f, "ciphertext is not valid for the key"),
+            MtaError::MissingProof => write!(f, "response does not carry an affine operation proof"),
+            MtaError::InvalidProof => write!(f, "affine operation proof does not verify"),
+        }
+    }
+}
+
+impl std::error::Error for MtaError {}
+
+#[derive(Debug, Copy, Clone)]
+pub struct MtaRequest<C: CurveArithmetic, const D: usize> {
+    pub c: NonZero<Uint<D>>,
+    curve: PhantomData<C>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct MtaResponse<C: CurveArithmetic, const S: usize, const D: usize> {
+    pub d: NonZero<Uint<D>>,
+    pub proof: Option<MtaProof<C, S, D>>,
+}
+
+// y is Bob's additive mask encrypted under his own key, it binds the mask used in d to the affine proof
+#[derive(Debug, Copy, Clone)]
+pub struct MtaProof<C: CurveArithmetic, const S: usize, const D: usize> {
+    pub y: NonZero<Uint<D>>,
+    pub affine: AffineProof<C, S, D>,
+}
+
+impl<C: CurveArithmetic, const D: usize> MtaRequest<C, D> {
+    pub fn from_ciphertext(c: NonZero<Uint<D>>) -> Self {
+        MtaRequest { c, curve: PhantomData }
+    }
+}
+
+impl<C, const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> MtaRequest<C, D>
+where
+    C: CurveArithmetic,
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn new<R: CryptoRng + ?Sized>(
+        alice_key: &PublicKey<S, D>,
+        a: &C::Scalar,
+        rng: &mut R,
+    ) -> Result<Self, MtaError> {
+        let ranges = CurveRanges::new::<C, S>();
+        if !ranges.fits(&alice_key.n, ell_prime(&ranges)) {
+            return Err(MtaError::ModulusTooShort);
+        }
+
+        let (c, _) = alice_key.encrypt(&scalar_to_uint::<C, S>(a), rng);
+        Ok(MtaRequest::from_ciphertext(c))
+    }
+
+    pub fn respond<R: CryptoRng + ?Sized>(
+        &self,
+        alice_key: &PublicKey<S, D>,
+        b: &C::Scalar,
+        rng: &mut R,
+    ) -> Result<(MtaResponse<C, S, D>, C::Scalar), MtaError> {
+        let ranges = CurveRanges::new::<C, S>();
+        if !ranges.fits(&alice_key.n, ell_prime(&ranges)) {
+            return Err(MtaError::ModulusTooShort);
+        }
+        if !bool::from(alice_key.ciphertext_is_valid(&self.c)) {
+            return Err(MtaError::InvalidCiphertext);
+        }
+
+        let beta_prime = Uint::<S>::random_bits(rng, ell_prime(&ranges));
+        let (mask, _) = alice_key.encrypt(&beta_prime, rng);
+        let d = alice_key.ciphertext_add(
+            &alice_key.ciphertext_mul_scalar(&self.c, &scalar_to_uint::<C, S>(b)),
+            &mask,
+        );
+
+        let response = MtaResponse { d, proof: None };
+        Ok((response, -uint_to_scalar::<C, S>(&beta_prime)))
+    }
+
+    // bob_key is Bob's own paillier key and setup is Alice's ring-Pedersen setup
+    pub fn respond_with_proof<R: CryptoRng + ?Sized>(
+        &self,
+        alice_key: &PublicKey<S, D>,
+        bob_key: &PublicKey<S, D>,
+        setup: &RingPedersenParams<S>,
+        b: &C::Scalar,
+        rng: &mut R,
+    ) -> Result<(MtaResponse<C, S, D>, C::Scalar), MtaError> {
+        let ranges = CurveRanges::new::<C, S>();
+        if !ranges.fits(&alice_key.n, ell_prime(&ranges)) || !ranges.fits(&bob_key.n, ell_prime(&ranges)) {
+            return Err(MtaError::ModulusTooShort);
+        }
+        if !bool::from(alice_key.ciphertext_is_valid(&self.c)) {
+            return Err(MtaError::InvalidCiphertext);
+        }
+
+        let b_uint = scalar_to_uint::<C, S>(b);
+        let beta_prime = Uint::<S>::random_bits(rng, ell_prime(&ranges));
+        let (mask, rho) = alice_key.encrypt(&beta_prime, rng);
+        let (y, rho_y) = bob_key.encrypt(&beta_prime, rng);
+        let d = alice_key.ciphertext_add(&alice_key.ciphertext_mul_scalar(&self.c, &b_uint), &mask);
+
+        let x = C::ProjectivePoint::mul_by_generator(b);
+        let statement = AffineStatement {
+            verifier_key: alice_key,
+            prover_key: bob_key,
+            setup,
+            c: &self.c,
+            d: &d,
+            y: &y,
+            x: &x,
+        };
+        let witness = AffineWitness {
+            x: &b_uint,
+            y: &beta_prime,
+            rho: &rho,
+            rho_y: &rho_y,
+        };
+        let affine = AffineProof::prove(&statement, &witness, rng);
+
+        let response = MtaResponse {
+            d,
+            proof: Some(MtaProof { y, affine }),
+        };
+        Ok((response, -uint_to_scalar::<C, S>(&beta_prime)))
+    }
+}
+
+impl<C, const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> MtaResponse<C, S, D>
+where
+    C: CurveArithmetic,
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    // x is the public commitment b * G to Bob's multiplicative share
+    pub fn verify(
+        &self,
+        alice_key: &PublicKey<S, D>,
+        bob_key: &PublicKey<S, D>,
+        setup: &RingPedersenParams<S>,
+        request: &MtaRequest<C, D>,
+        x: &C::ProjectivePoint,
+    ) -> Result<(), MtaError> {
+        let proof = self.proof.as_ref().ok_or(MtaError::MissingProof)?;
+        let statement = AffineStatement {
+            verifier_key: alice_key,
+            prover_key: bob_key,
+            setup,
+            c: &request.c,
+            d: &self.d,
+            y: &proof.y,
+            x,
+        };
+
+        if proof.affine.verify(&statement) {
+            Ok(())
+        } else {
+            Err(MtaError::InvalidProof)
+        }
+    }
+
+    pub fn finish<K>(&self, alice_key: &K) -> Result<C::Scalar, MtaError>
+    where
+        K: DecryptionKey<Uint<S>, Ciphertext = NonZero<Uint<D>>>,
+    {
+        let alpha = Option::<Uint<S>>::from(alice_key.try_decrypt(&self.d)).ok_or(MtaError::InvalidCiphertext)?;
+        Ok(uint_to_scalar::<C, S>(&alpha))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::random_scalar;
+    use crate::mta::{MtaError, MtaRequest};
+    use crate::ring_pedersen::RingPedersenSecret;
+    use crate::{KeyGenerator, PaillierSecretKey2048};
+    use elliptic_curve::ops::MulByGenerator;
+    use k256::{ProjectivePoint, Scalar, Secp256k1};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    #[test]
+    fn should_convert_multiplicative_shares() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (alice_sk, alice_pk) = PaillierSecretKey2048::random(&mut rng);
+        let a = random_scalar::<Secp256k1, _>(&mut rng);
+        let b = random_scalar::<Secp256k1, _>(&mut rng);
+
+        let req = MtaRequest::<Secp256k1, _>::new(&alice_pk, &a, &mut rng).unwrap();
+        let (resp, beta) = req.respond(&alice_pk, &b, &mut rng).unwrap();
+        let alpha = resp.finish(&alice_sk).unwrap();
+
+        assert_eq!(alpha + beta, a * b);
+        assert_ne!(beta, Scalar::ZERO);
+    }
+
+    #[test]
+    fn should_prove_and_verify_affine_operation() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (alice_sk, _) = PaillierSecretKey2048::builder().safe_primes(true).generate(&mut rng);
+        let alice_pk = alice_sk.as_public_key();
+        let (_, bob_pk) = PaillierSecretKey2048::random(&mut rng);
+        let setup = *RingPedersenSecret::from_secret_key(&alice_sk, &mut rng).params();
+
+        let a = random_scalar::<Secp256k1, _>(&mut rng);
+        let b = random_scalar::<Secp256k1, _>(&mut rng);
+        let x = ProjectivePoint::mul_by_generator(&b);
+
+        let req = MtaRequest::<Secp256k1, _>::new(&alice_pk, &a, &mut rng).unwrap();
+        let (resp, beta) = req
+            .respond_with_proof(&alice_pk, &bob_pk, &setup, &b, &mut rng)
+            .unwrap();
+        assert_eq!(resp.verify(&alice_pk, &bob_pk, &setup, &req, &x), Ok(()));
+        assert_eq!(resp.finish(&alice_sk).unwrap() + beta, a * b);
+
+        let wrong_x = ProjectivePoint::mul_by_generator(&(b + Scalar::ONE));
+        assert_eq!(
+            resp.verify(&alice_pk, &bob_pk, &setup, &req, &wrong_x),
+            Err(MtaError::InvalidProof)
+        );
+
+        let (plain, _) = req.respond(&alice_pk, &b, &mut rng).unwrap();
+        assert_eq!(
+            plain.verify(&alice_pk, &bob_pk, &setup, &req, &x),
+            Err(MtaError::MissingProof)
+        );
+
+        let mut tampered = resp;
+        tampered.d = plain.d;
+        assert_eq!(
+            tampered.verify(&alice_pk, &bob_pk, &setup, &req, &x),
+            Err(MtaError::InvalidProof)
+        );
+    }
+}
@@ -0,0 +1,369 @@
+use crate::pk::{BatchError, PublicKey};
+use crate::sk::SecretKey;
+use crate::traits::{DecryptionKey, EncryptionKey, HomomorphicKey, Key};
+use crate::zk::MembershipProof;
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, Split, Uint};
+use rand_core::CryptoRng;
+use std::fmt;
+
+// 1-out-of-n oblivious transfer: the receiver sends encryptions of the unit vector e_choice under its own key, the
+// sender answers sum_i Enc(e_i) m_i re-randomized with a fresh encryption of zero, the receiver decrypts m_choice;
+// every slot carries a proof that it encrypts a bit and their sum a proof that it encrypts 1, so a receiver cannot
+// select a combination of several messages
+
+const PROOF_CONTEXT: &[u8] = b"paillier-ot-request";
+
+// request frame: selection frame length (4 bytes) | selection frame | bit proof per slot | sum proof
+const REQUEST_HEADER_LEN: usize = 4;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OtError {
+    NoMessages,
+    ChoiceOutOfRange,
+    InvalidMessage(usize),
+    LengthMismatch,
+    InvalidCiphertext(usize),
+    InvalidProof,
+    Encoding(BatchError),
+}
+
+impl fmt::Display for OtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OtError::NoMessages => write!(f, "there are no messages to choose from"),
+            OtError::ChoiceOutOfRange => write!(f, "choice is out of range"),
+            OtError::InvalidMessage(i) => write!(f, "message {i} is not smaller than the modulus"),
+            OtError::LengthMismatch => write!(f, "request does not match the number of messages"),
+            OtError::InvalidCiphertext(i) => write!(f, "ciphertext {i} is not valid for the key"),
+            OtError::InvalidProof => write!(f, "request is not proven to select exactly one message"),
+            OtError::Encoding(e) => write!(f, "malformed message: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for OtError {}
+
+impl From<BatchError> for OtError {
+    fn from(e: BatchError) -> Self {
+        OtError::Encoding(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtRequest<const S: usize, const D: usize> {
+    pub selection: Vec<NonZero<Uint<D>>>,
+    pub proofs: Vec<MembershipProof<S, D>>,
+    pub sum_proof: MembershipProof<S, D>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OtResponse<const D: usize> {
+    pub c: NonZero<Uint<D>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct OtReceiver<'a, const H: usize, const S: usize, const D: usize> {
+    sk: &'a SecretKey<H, S, D>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OtSender<const S: usize, const D: usize> {
+    key: PublicKey<S, D>,
+    messages: Vec<Uint<S>>,
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> OtRequest<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn to_bytes(&self, key: &PublicKey<S, D>) -> Vec<u8> {
+        let selection = key.ciphertext_batch_to_bytes(&self.selection, true);
+        let frame_len = u32::try_from(selection.len()).expect("request is too large");
+
+        let mut bytes = frame_len.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&selection);
+        for proof in self.proofs.iter().chain([&self.sum_proof]) {
+            bytes.extend_from_slice(&proof.to_bytes(key));
+        }
+        bytes
+    }
+
+    pub fn from_bytes(key: &PublicKey<S, D>, bytes: &[u8]) -> Result<Self, OtError> {
+        let (header, body) = bytes
+            .split_at_checked(REQUEST_HEADER_LEN)
+            .ok_or(BatchError::Truncated)?;
+        let frame_len = u32::from_be_bytes(header.try_into().expect("header has 4 bytes of length")) as usize;
+        let (frame, proofs) = body.split_at_checked(frame_len).ok_or(BatchError::Truncated)?;
+        let selection = key.ciphertext_batch_from_bytes(frame)?;
+
+        let bit_proof_len = MembershipProof::<S, D>::encoded_len(2);
+        let sum_proof_len = MembershipProof::<S, D>::encoded_len(1);
+        if Some(proofs.len())
+            != selection
+                .len()
+                .checked_mul(bit_proof_len)
+                .map(|len| len + sum_proof_len)
+        {
+            return Err(BatchError::Truncated.into());
+        }
+        let (proofs, sum_proof) = proofs.split_at(proofs.len() - sum_proof_len);
+        let proofs = proofs
+            .chunks_exact(bit_proof_len)
+            .map(|proof| MembershipProof::from_bytes(key, proof))
+            .collect::<Option<_>>()
+            .ok_or(OtError::InvalidProof)?;
+        let sum_proof = MembershipProof::from_bytes(key, sum_proof).ok_or(OtError::InvalidProof)?;
+
+        Ok(OtRequest {
+            selection,
+            proofs,
+            sum_proof,
+        })
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> OtResponse<D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn to_bytes(&self, key: &PublicKey<S, D>) -> Vec<u8> {
+        key.ciphertext_batch_to_bytes(&[self.c], true)
+    }
+
+    pub fn from_bytes(key: &PublicKey<S, D>, bytes: &[u8]) -> Result<Self, OtError> {
+        match key.ciphertext_batch_from_bytes(bytes)?.as_slice() {
+            [c] => Ok(OtResponse { c: *c }),
+            _ => Err(OtError::LengthMismatch),
+        }
+    }
+}
+
+impl<
+    'a,
+    const H: usize,
+    const H_UNSAT: usize,
+    const S: usize,
+    const S_UNSAT: usize,
+    const D: usize,
+    const D_UNSAT: usize,
+    const Q: usize,
+> OtReceiver<'a, H, S, D>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn new<R: CryptoRng + ?Sized>(
+        sk: &'a SecretKey<H, S, D>,
+        messages: usize,
+        choice: usize,
+        rng: &mut R,
+    ) -> Result<(Self, OtRequest<S, D>), OtError> {
+        if messages == 0 {
+            return Err(OtError::NoMessages);
+        }
+        if choice >= messages {
+            return Err(OtError::ChoiceOutOfRange);
+        }
+
+        let key = &sk.pk;
+        let encryptions: Vec<_> = (0..messages)
+            .map(|i| key.encrypt(&Uint::from_u8((i == choice) as u8), rng))
+            .collect();
+        let proofs = encryptions
+            .iter()
+            .enumerate()
+            .map(|(i, encryption)| MembershipProof::prove_bit(key, PROOF_CONTEXT, i == choice, encryption, rng))
+            .collect();
+        let sum = encryptions
+            .iter()
+            .skip(1)
+            .fold(encryptions[0], |(c_acc, r_acc), (c, r)| {
+                (key.ciphertext_add(&c_acc, c), key.nonce_add(&r_acc, r))
+            });
+        let sum_proof = MembershipProof::prove(key, PROOF_CONTEXT, &[Uint::ONE], 0, &sum, rng);
+
+        let request = OtRequest {
+            selection: encryptions.iter().map(|(c, _)| *c).collect(),
+            proofs,
+            sum_proof,
+        };
+        Ok((OtReceiver { sk }, request))
+    }
+
+    pub fn finish(self, response: &OtResponse<D>) -> Result<Uint<S>, OtError> {
+        Option::from(self.sk.try_decrypt(&response.c)).ok_or(OtError::InvalidCiphertext(0))
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> OtSender<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    // the key is the receiver's public key
+    pub fn new(key: PublicKey<S, D>, messages: Vec<Uint<S>>) -> Result<Self, OtError> {
+        if messages.is_empty() {
+            return Err(OtError::NoMessages);
+        }
+        if let Some(i) = messages.iter().position(|m| !bool::from(key.scalar_is_valid(m))) {
+            return Err(OtError::InvalidMessage(i));
+        }
+
+        Ok(OtSender { key, messages })
+    }
+
+    pub fn respond<R: CryptoRng + ?Sized>(
+        self,
+        request: &OtRequest<S, D>,
+        rng: &mut R,
+    ) -> Result<OtResponse<D>, OtError> {
+        let key = &self.key;
+        if request.selection.len() != self.messages.len() {
+            return Err(OtError::LengthMismatch);
+        }
+        if let Some(i) = request
+            .selection
+            .iter()
+            .position(|c| !bool::from(key.ciphertext_is_valid(c)))
+        {
+            return Err(OtError::InvalidCiphertext(i));
+        }
+        if !self.is_unit_vector(request, rng) {
+            return Err(OtError::InvalidProof);
+        }
+
+        let c = request
+            .selection
+            .iter()
+            .zip(&self.messages)
+            .fold(key.encrypt(&Uint::ZERO, rng).0, |acc, (e, m)| {
+                key.ciphertext_add(&acc, &key.ciphertext_mul_scalar(e, m))
+            });
+        Ok(OtResponse { c })
+    }
+
+    fn is_unit_vector<R: CryptoRng + ?Sized>(&self, request: &OtRequest<S, D>, rng: &mut R) -> bool {
+        let key = &self.key;
+        let contexts = vec![PROOF_CONTEXT; request.selection.len()];
+        let sum = request
+            .selection
+            .iter()
+            .skip(1)
+            .fold(request.selection[0], |acc, c| key.ciphertext_add(&acc, c));
+
+        MembershipProof::verify_batch(
+            key,
+            &[Uint::ZERO, Uint::ONE],
+            &contexts,
+            &request.selection,
+            &request.proofs,
+            rng,
+        ) && request.sum_proof.verify(key, PROOF_CONTEXT, &[Uint::ONE], &sum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::KeyGenerator;
+    use crate::ot::{OtError, OtReceiver, OtRequest, OtResponse, OtSender};
+    use crate::pk::BatchError;
+    use crate::sk::SecretKey;
+    use crypto_bigint::{U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+
+    fn transfer(seed: u64, choice: usize) -> (Vec<u8>, Vec<u8>, U512) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let messages: Vec<_> = (0..5u64).map(|i| U512::from_u64(1000 + i)).collect();
+
+        let (receiver, request) = OtReceiver::new(&sk, messages.len(), choice, &mut rng).unwrap();
+        let request_bytes = request.to_bytes(&pk);
+
+        let request = OtRequest::from_bytes(&pk, &request_bytes).unwrap();
+        let response = OtSender::new(pk, messages)
+            .unwrap()
+            .respond(&request, &mut rng)
+            .unwrap();
+        let response_bytes = response.to_bytes(&pk);
+
+        let response = OtResponse::from_bytes(&pk, &response_bytes).unwrap();
+        (request_bytes, response_bytes, receiver.finish(&response).unwrap())
+    }
+
+    #[test]
+    fn should_transfer_chosen_message() {
+        for choice in 0..5 {
+            let (_, _, m) = transfer(choice as u64, choice);
+            assert_eq!(m, U512::from_u64(1000 + choice as u64));
+        }
+    }
+
+    #[test]
+    fn should_reproduce_seeded_transcript() {
+        let (request1, response1, _) = transfer(42, 3);
+        let (request2, response2, _) = transfer(42, 3);
+        let (request3, _, _) = transfer(42, 1);
+
+        assert_eq!(request1, request2);
+        assert_eq!(response1, response2);
+        assert_ne!(request1, request3);
+    }
+
+    #[test]
+    fn should_reject_mismatched_request() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+
+        let (_, request) = OtReceiver::new(&sk, 3, 0, &mut rng).unwrap();
+        let sender = OtSender::new(pk, vec![U512::ONE; 4]).unwrap();
+        assert_eq!(sender.respond(&request, &mut rng).unwrap_err(), OtError::LengthMismatch);
+        assert_eq!(
+            OtReceiver::new(&sk, 3, 3, &mut rng).unwrap_err(),
+            OtError::ChoiceOutOfRange
+        );
+    }
+
+    #[test]
+    fn should_reject_request_selecting_several_messages() {
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let sender = OtSender::new(pk, vec![U512::ONE; 3]).unwrap();
+
+        // both of the first two slots encrypt 1, every slot still carries a valid bit proof
+        let (_, first) = OtReceiver::new(&sk, 3, 0, &mut rng).unwrap();
+        let (_, mut request) = OtReceiver::new(&sk, 3, 1, &mut rng).unwrap();
+        request.selection[0] = first.selection[0];
+        request.proofs[0] = first.proofs[0].clone();
+        assert_eq!(
+            sender.clone().respond(&request, &mut rng).unwrap_err(),
+            OtError::InvalidProof
+        );
+
+        let bytes = request.to_bytes(&pk);
+        assert_eq!(OtRequest::from_bytes(&pk, &bytes).unwrap(), request);
+        assert_eq!(
+            OtRequest::from_bytes(&pk, &bytes[..bytes.len() - 1]).unwrap_err(),
+            OtError::Encoding(BatchError::Truncated)
+        );
+    }
+}
@@ -0,0 +1,5 @@
+mod pk;
+mod sk;
+
+pub use crate::ou::pk::PublicKey;
+pub use crate::ou::sk::SecretKey;
@@ -0,0 +1,299 @@
+use crate::traits::{EncryptionKey, HomomorphicKey, Key};
+use crypto_bigint::modular::{MontyForm, MontyParams, SafeGcdInverter};
+use crypto_bigint::{NonZero, Odd, PrecomputeInverter, RandomMod, Uint};
+use rand_core::CryptoRng;
+use subtle::{Choice, ConstantTimeEq, ConstantTimeLess};
+
+// Okamoto-Uchiyama over n = p^2 q with h = g^n, plaintexts are integers below 2^(k - 1) <= p for k bit primes,
+// c = g^m h^r mod n; the nonce is kept as the group element h^r so that it can be recovered by the key owner
+#[derive(Debug, Copy, Clone)]
+pub struct PublicKey<const H: usize, const N: usize> {
+    pub(crate) n: Odd<Uint<N>>,
+    pub(crate) g: Uint<N>,
+    pub(crate) h: Uint<N>,
+    pub(crate) n_monty_params: MontyParams<N>,
+}
+
+impl<const H: usize, const N: usize> PublicKey<H, N> {
+    pub fn from_parts_unchecked(n: Odd<Uint<N>>, g: Uint<N>, h: Uint<N>) -> Self {
+        let n_monty_params = MontyParams::new_vartime(n);
+
+        PublicKey {
+            n,
+            g,
+            h,
+            n_monty_params,
+        }
+    }
+
+    pub fn n(&self) -> &Odd<Uint<N>> {
+        &self.n
+    }
+
+    pub fn g(&self) -> &Uint<N> {
+        &self.g
+    }
+
+    pub fn h(&self) -> &Uint<N> {
+        &self.h
+    }
+
+    pub fn plaintext_bits(&self) -> u32 {
+        Uint::<H>::BITS - 1
+    }
+
+    pub(crate) fn g_to(&self, m: &Uint<H>) -> Uint<N> {
+        MontyForm::new(&self.g, self.n_monty_params).pow(m).retrieve()
+    }
+
+    fn mul_mod_n(&self, x: &Uint<N>, y: &Uint<N>) -> NonZero<Uint<N>> {
+        let product = MontyForm::new(x, self.n_monty_params) * MontyForm::new(y, self.n_monty_params);
+        product.retrieve().to_nz().expect("units are non zero")
+    }
+}
+
+impl<const H: usize, const N: usize, const N_UNSAT: usize> PublicKey<H, N>
+where
+    Odd<Uint<N>>: PrecomputeInverter<Inverter = SafeGcdInverter<N, N_UNSAT>>,
+{
+    pub fn random_plaintext<R: CryptoRng + ?Sized>(&self, rng: &mut R) -> Uint<H> {
+        Uint::random_mod(
+            rng,
+            &Uint::ONE
+                .shl_vartime(self.plaintext_bits())
+                .to_nz()
+                .expect("bound is non zero"),
+        )
+    }
+
+    pub fn random_nonce<R: CryptoRng + ?Sized>(&self, rng: &mut R) -> NonZero<Uint<N>> {
+        let r = Uint::<N>::random_mod(rng, self.n.as_nz_ref());
+        let h_to_r = MontyForm::new(&self.h, self.n_monty_params).pow(&r).retrieve();
+
+        h_to_r.to_nz().expect("h is a unit")
+    }
+
+    fn inv_mod_n(&self, x: &Uint<N>) -> NonZero<Uint<N>> {
+        x.inv_odd_mod(&self.n)
+            .expect("units are invertible")
+            .to_nz()
+            .expect("units are non zero")
+    }
+}
+
+impl<const H: usize, const N: usize, const N_UNSAT: usize> Key<Uint<H>> for PublicKey<H, N>
+where
+    Odd<Uint<N>>: PrecomputeInverter<Inverter = SafeGcdInverter<N, N_UNSAT>>,
+{
+    type Ciphertext = NonZero<Uint<N>>;
+    type Nonce = NonZero<Uint<N>>;
+
+    fn plaintext_is_valid(&self, m: &Uint<H>) -> Choice {
+        m.ct_lt(&Uint::ONE.shl_vartime(self.plaintext_bits()))
+    }
+
+    fn plaintext_eq(&self, ml: &Uint<H>, mr: &Uint<H>) -> Choice {
+        self.plaintext_is_valid(ml) & self.plaintext_is_valid(mr) & ml.ct_eq(mr)
+    }
+
+    fn ciphertext_is_valid(&self, c: &Self::Ciphertext) -> Choice {
+        c.ct_lt(&self.n) & c.gcd(&self.n).ct_eq(&Uint::ONE)
+    }
+
+    fn ciphertext_eq(&self, cl: &Self::Ciphertext, cr: &Self::Ciphertext) -> Choice {
+        self.ciphertext_is_valid(cl) & self.ciphertext_is_valid(cr) & cl.ct_eq(cr)
+    }
+
+    // only units are checked, whether the nonce lies in the subgroup generated by h is not publicly decidable
+    fn nonce_is_valid(&self, u: &Self::Nonce) -> Choice {
+        u.ct_lt(&self.n) & u.gcd(&self.n).ct_eq(&Uint::ONE)
+    }
+
+    fn nonce_eq(&self, ul: &Self::Nonce, ur: &Self::Nonce) -> Choice {
+        self.nonce_is_valid(ul) & self.nonce_is_valid(ur) & ul.ct_eq(ur)
+    }
+}
+
+impl<const H: usize, const N: usize, const N_UNSAT: usize> EncryptionKey<Uint<H>> for PublicKey<H, N>
+where
+    Odd<Uint<N>>: PrecomputeInverter<Inverter = SafeGcdInverter<N, N_UNSAT>>,
+{
+    fn encrypt_with_nonce(&self, m: &Uint<H>, u: &Self::Nonce) -> Self::Ciphertext {
+        self.mul_mod_n(&self.g_to(m), u)
+    }
+
+    fn encrypt<R: CryptoRng + ?Sized>(&self, m: &Uint<H>, rng: &mut R) -> (Self::Ciphertext, Self::Nonce) {
+        let u = self.random_nonce(rng);
+        let c = self.encrypt_with_nonce(m, &u);
+        (c, u)
+    }
+}
+
+impl<const H: usize, const N: usize, const N_UNSAT: usize> HomomorphicKey<Uint<H>> for PublicKey<H, N>
+where
+    Odd<Uint<N>>: PrecomputeInverter<Inverter = SafeGcdInverter<N, N_UNSAT>>,
+{
+    type Scalar = Uint<H>;
+
+    fn scalar_is_valid(&self, _s: &Self::Scalar) -> Choice {
+        // plaintexts live modulo the secret p, every integer scalar is meaningful
+        Choice::from(1)
+    }
+
+    fn scalar_eq(&self, sl: &Self::Scalar, sr: &Self::Scalar) -> Choice {
+        sl.ct_eq(sr)
+    }
+
+    fn ciphertext_add(&self, cl: &Self::Ciphertext, cr: &Self::Ciphertext) -> Self::Ciphertext {
+        self.mul_mod_n(cl, cr)
+    }
+
+    fn ciphertext_add_plain(&self, c: &Self::Ciphertext, m: &Uint<H>) -> Self::Ciphertext {
+        self.mul_mod_n(c, &self.g_to(m))
+    }
+
+    fn ciphertext_sub(&self, cl: &Self::Ciphertext, cr: &Self::Ciphertext) -> Self::Ciphertext {
+        self.mul_mod_n(cl, &self.inv_mod_n(cr))
+    }
+
+    fn ciphertext_sub_plain(&self, c: &Self::Ciphertext, m: &Uint<H>) -> Self::Ciphertext {
+        self.mul_mod_n(c, &self.inv_mod_n(&self.g_to(m)))
+    }
+
+    fn ciphertext_neg(&self, c: &Self::Ciphertext) -> Self::Ciphertext {
+        self.inv_mod_n(c)
+    }
+
+    fn ciphertext_mul_scalar(&self, c: &Self::Ciphertext, s: &Self::Scalar) -> Self::Ciphertext {
+        let c_monty_form = MontyForm::new(c, self.n_monty_params);
+        c_monty_form.pow(s).retrieve().to_nz().expect("c is non zero")
+    }
+
+    fn nonce_add(&self, ul: &Self::Nonce, ur: &Self::Nonce) -> Self::Nonce {
+        self.mul_mod_n(ul, ur)
+    }
+
+    fn nonce_sub(&self, ul: &Self::Nonce, ur: &Self::Nonce) -> Self::Nonce {
+        self.mul_mod_n(ul, &self.inv_mod_n(ur))
+    }
+
+    fn nonce_neg(&self, u: &Self::Nonce) -> Self::Nonce {
+        self.inv_mod_n(u)
+    }
+
+    fn nonce_mul_scalar(&self, u: &Self::Nonce, s: &Self::Scalar) -> Self::Nonce {
+        let u_monty_form = MontyForm::new(u, self.n_monty_params);
+        u_monty_form.pow(s).retrieve().to_nz().expect("u is non zero")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::traits::HomomorphicKey;
+    use crate::{DecryptionKey, EncryptionKey, KeyGenerator, OkamotoUchiyamaSecretKey1536, OpeningKey};
+    use crypto_bigint::{NonZero, U512, U2048};
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::SeedableRng;
+
+    // plaintexts are reduced modulo p while g has a larger order modulo n, so the opened nonce is only
+    // determined up to the reduction, the opening has to reproduce the ciphertext though
+    fn assert_opening(sk: &OkamotoUchiyamaSecretKey1536, c: &NonZero<U2048>, expected: &U512) {
+        let (m, u) = sk.open(c);
+        assert_eq!(&m, expected);
+        assert_eq!(&sk.pk.encrypt_with_nonce(&m, &u), c);
+    }
+
+    #[test]
+    fn should_homomorphic_add() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = OkamotoUchiyamaSecretKey1536::random(&mut rng);
+
+        let m1 = pk.random_plaintext(&mut rng);
+        let m2 = pk.random_plaintext(&mut rng);
+        let (c1, u1) = pk.encrypt(&m1, &mut rng);
+        let (c2, u2) = pk.encrypt(&m2, &mut rng);
+
+        let c = pk.ciphertext_add(&c1, &c2);
+        assert_eq!(c, pk.encrypt_with_nonce(&m1.wrapping_add(&m2), &pk.nonce_add(&u1, &u2)));
+        assert_opening(&sk, &c, &m1.add_mod(&m2, sk.p.as_ref()));
+    }
+
+    #[test]
+    fn should_homomorphic_add_plain() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = OkamotoUchiyamaSecretKey1536::random(&mut rng);
+
+        let m1 = pk.random_plaintext(&mut rng);
+        let m2 = pk.random_plaintext(&mut rng);
+        let (c1, u1) = pk.encrypt(&m1, &mut rng);
+
+        let c = pk.ciphertext_add_plain(&c1, &m2);
+        assert_eq!(c, pk.encrypt_with_nonce(&m1.wrapping_add(&m2), &u1));
+        assert_opening(&sk, &c, &m1.add_mod(&m2, sk.p.as_ref()));
+    }
+
+    #[test]
+    fn should_homomorphic_sub() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = OkamotoUchiyamaSecretKey1536::random(&mut rng);
+
+        let m1 = pk.random_plaintext(&mut rng);
+        let m2 = pk.random_plaintext(&mut rng);
+        let (m1, m2) = if m1 >= m2 { (m1, m2) } else { (m2, m1) };
+        let (c1, u1) = pk.encrypt(&m1, &mut rng);
+        let (c2, u2) = pk.encrypt(&m2, &mut rng);
+
+        let c = pk.ciphertext_sub(&c1, &c2);
+        assert_eq!(c, pk.encrypt_with_nonce(&m1.wrapping_sub(&m2), &pk.nonce_sub(&u1, &u2)));
+        assert_opening(&sk, &c, &m1.wrapping_sub(&m2));
+    }
+
+    #[test]
+    fn should_homomorphic_sub_plain() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = OkamotoUchiyamaSecretKey1536::random(&mut rng);
+
+        let m1 = pk.random_plaintext(&mut rng);
+        let m2 = pk.random_plaintext(&mut rng);
+        let (c1, u1) = pk.encrypt(&m1, &mut rng);
+
+        let c = pk.ciphertext_sub_plain(&c1, &m2);
+        if m1 >= m2 {
+            assert_eq!(c, pk.encrypt_with_nonce(&m1.wrapping_sub(&m2), &u1));
+        }
+        assert_opening(&sk, &c, &m1.sub_mod(&m2, sk.p.as_ref()));
+    }
+
+    #[test]
+    fn should_homomorphic_neg() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = OkamotoUchiyamaSecretKey1536::random(&mut rng);
+
+        let m1 = pk.random_plaintext(&mut rng);
+        let (c1, u1) = pk.encrypt(&m1, &mut rng);
+
+        let c = pk.ciphertext_neg(&c1);
+        let g_to_m1 = pk.encrypt_with_nonce(&m1, &NonZero::ONE);
+        assert_eq!(pk.ciphertext_add(&c, &g_to_m1), pk.nonce_neg(&u1));
+        assert_opening(&sk, &c, &m1.neg_mod(sk.p.as_ref()));
+    }
+
+    #[test]
+    fn should_homomorphic_mul_scalar() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = OkamotoUchiyamaSecretKey1536::random(&mut rng);
+
+        let m1 = pk.random_plaintext(&mut rng);
+        let s = pk.random_plaintext(&mut rng);
+        let (c1, u1) = pk.encrypt(&m1, &mut rng);
+
+        let c = pk.ciphertext_mul_scalar(&c1, &s);
+        let g_to_m1 = pk.encrypt_with_nonce(&m1, &NonZero::ONE);
+        assert_eq!(
+            pk.ciphertext_sub(&c, &pk.ciphertext_mul_scalar(&g_to_m1, &s)),
+            pk.nonce_mul_scalar(&u1, &s)
+        );
+        assert_opening(&sk, &c, &m1.mul_mod(&s, sk.p.as_nz_ref()));
+        assert_eq!(sk.decrypt(&pk.ciphertext_mul_scalar(&c1, &U512::ZERO)), U512::ZERO);
+    }
+}
@@ -0,0 +1,216 @@
+use crate::ou::pk::PublicKey;
+use crate::traits::{DecryptionKey, Key, KeyGenerator, OpeningKey};
+use crate::utils::{fermat_quotient, odd_widening_square};
+use crypto_bigint::modular::{MontyForm, MontyParams, SafeGcdInverter};
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, RandomMod, Split, Uint};
+use crypto_primes::RandomPrimeWithRng;
+use rand_core::CryptoRng;
+use subtle::{ConditionallySelectable, CtOption};
+
+#[derive(Debug, Copy, Clone)]
+pub struct SecretKey<const H: usize, const S: usize, const N: usize> {
+    pub(crate) pk: PublicKey<H, N>,
+    pub(crate) p: Odd<Uint<H>>,
+    pub(crate) pm1: Uint<H>,
+    pub(crate) pp_monty_params: MontyParams<S>,
+    // 1 / L(g^(p - 1) mod p^2) mod p
+    pub(crate) lg_inv: Uint<H>,
+}
+
+impl<const H: usize, const H_UNSAT: usize, const S: usize, const N: usize, const N_UNSAT: usize> SecretKey<H, S, N>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<N>>,
+    Uint<N>: Split<Output = Uint<S>>,
+    Odd<Uint<N>>: PrecomputeInverter<Inverter = SafeGcdInverter<N, N_UNSAT>>,
+{
+    pub fn from_primes<R: CryptoRng + ?Sized>(p: Odd<Uint<H>>, q: Odd<Uint<H>>, rng: &mut R) -> Self {
+        if p == q
+            || p.bits() != Uint::<H>::BITS
+            || q.bits() != Uint::<H>::BITS
+            || !p.as_ref().is_prime_with_rng(rng)
+            || !q.as_ref().is_prime_with_rng(rng)
+        {
+            panic!("p and q must be prime and have the same length");
+        }
+
+        Self::from_primes_unchecked(p, q, rng)
+    }
+
+    // g is drawn at random until g^(p - 1) has order p modulo p^2
+    pub fn from_primes_unchecked<R: CryptoRng + ?Sized>(p: Odd<Uint<H>>, q: Odd<Uint<H>>, rng: &mut R) -> Self {
+        let pp = odd_widening_square(&p);
+        let n = pp
+            .widening_mul(&q.resize::<S>())
+            .to_odd()
+            .expect("n is a product of odd primes");
+        let pm1 = p.wrapping_sub(&Uint::ONE);
+        let pp_monty_params = MontyParams::new(pp);
+
+        loop {
+            let g = Uint::<N>::random_mod(rng, n.as_nz_ref());
+            if g.gcd(&n) != Uint::ONE {
+                continue;
+            }
+            let lg = fermat_quotient(&g, &p, &pp_monty_params, &pm1);
+            let Some(lg_inv) = Option::<Uint<H>>::from(lg.inv_odd_mod(&p)) else {
+                continue;
+            };
+
+            let n_monty_params = MontyParams::new_vartime(n);
+            let h = MontyForm::new(&g, n_monty_params).pow(n.as_ref()).retrieve();
+            return SecretKey {
+                pk: PublicKey::from_parts_unchecked(n, g, h),
+                p,
+                pm1,
+                pp_monty_params,
+                lg_inv,
+            };
+        }
+    }
+
+    pub fn as_public_key(&self) -> PublicKey<H, N> {
+        self.pk.to_owned()
+    }
+}
+
+impl<const H: usize, const H_UNSAT: usize, const S: usize, const N: usize, const N_UNSAT: usize> Key<Uint<H>>
+    for SecretKey<H, S, N>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<N>>,
+    Uint<N>: Split<Output = Uint<S>>,
+    Odd<Uint<N>>: PrecomputeInverter<Inverter = SafeGcdInverter<N, N_UNSAT>>,
+{
+    type Ciphertext = NonZero<Uint<N>>;
+    type Nonce = NonZero<Uint<N>>;
+
+    fn plaintext_is_valid(&self, m: &Uint<H>) -> subtle::Choice {
+        self.pk.plaintext_is_valid(m)
+    }
+
+    fn plaintext_eq(&self, ml: &Uint<H>, mr: &Uint<H>) -> subtle::Choice {
+        self.pk.plaintext_eq(ml, mr)
+    }
+
+    fn ciphertext_is_valid(&self, c: &Self::Ciphertext) -> subtle::Choice {
+        self.pk.ciphertext_is_valid(c)
+    }
+
+    fn ciphertext_eq(&self, cl: &Self::Ciphertext, cr: &Self::Ciphertext) -> subtle::Choice {
+        self.pk.ciphertext_eq(cl, cr)
+    }
+
+    fn nonce_is_valid(&self, u: &Self::Nonce) -> subtle::Choice {
+        self.pk.nonce_is_valid(u)
+    }
+
+    fn nonce_eq(&self, ul: &Self::Nonce, ur: &Self::Nonce) -> subtle::Choice {
+        self.pk.nonce_eq(ul, ur)
+    }
+}
+
+impl<const H: usize, const H_UNSAT: usize, const S: usize, const N: usize, const N_UNSAT: usize> DecryptionKey<Uint<H>>
+    for SecretKey<H, S, N>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<N>>,
+    Uint<N>: Split<Output = Uint<S>>,
+    Odd<Uint<N>>: PrecomputeInverter<Inverter = SafeGcdInverter<N, N_UNSAT>>,
+{
+    fn decrypt(&self, c: &Self::Ciphertext) -> Uint<H> {
+        // m = L(c^(p - 1) mod p^2) / L(g^(p - 1) mod p^2) mod p
+        let lc = fermat_quotient(c, &self.p, &self.pp_monty_params, &self.pm1);
+        lc.mul_mod(&self.lg_inv, self.p.as_nz_ref())
+    }
+}
+
+impl<const H: usize, const H_UNSAT: usize, const S: usize, const N: usize, const N_UNSAT: usize> OpeningKey<Uint<H>>
+    for SecretKey<H, S, N>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<N>>,
+    Uint<N>: Split<Output = Uint<S>>,
+    Odd<Uint<N>>: PrecomputeInverter<Inverter = SafeGcdInverter<N, N_UNSAT>>,
+{
+    fn open(&self, c: &Self::Ciphertext) -> (Uint<H>, Self::Nonce) {
+        // h^r = c / g^m mod n
+        let m = self.decrypt(c);
+        let g_to_m_inv = self.pk.g_to(&m).inv_odd_mod(&self.pk.n).expect("g is a unit");
+        let u = (MontyForm::new(c, self.pk.n_monty_params) * MontyForm::new(&g_to_m_inv, self.pk.n_monty_params))
+            .retrieve()
+            .to_nz()
+            .expect("units are non zero");
+
+        (m, u)
+    }
+
+    fn try_open(&self, c: &Self::Ciphertext) -> CtOption<(Uint<H>, Self::Nonce)> {
+        // the nonce of a multiple of n is zero, so invalid ciphertexts are swapped for a trivial one
+        let is_valid = self.ciphertext_is_valid(c);
+        let c_checked = NonZero::conditional_select(&NonZero::ONE, c, is_valid);
+
+        CtOption::new(self.open(&c_checked), is_valid)
+    }
+}
+
+impl<const H: usize, const H_UNSAT: usize, const S: usize, const N: usize, const N_UNSAT: usize> KeyGenerator<Uint<H>>
+    for SecretKey<H, S, N>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<N>>,
+    Uint<N>: Split<Output = Uint<S>>,
+    Odd<Uint<N>>: PrecomputeInverter<Inverter = SafeGcdInverter<N, N_UNSAT>>,
+{
+    type EncryptionKey = PublicKey<H, N>;
+
+    fn random<R: CryptoRng + ?Sized>(rng: &mut R) -> (Self, Self::EncryptionKey) {
+        let mut p = Uint::ZERO;
+        let mut q = Uint::ZERO;
+        while p == q {
+            p = Uint::generate_prime_with_rng(rng, Uint::<H>::BITS);
+            q = Uint::generate_prime_with_rng(rng, Uint::<H>::BITS);
+        }
+
+        let sk = Self::from_primes_unchecked(p.to_odd().unwrap(), q.to_odd().unwrap(), rng);
+        let pk = sk.pk;
+        (sk, pk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DecryptionKey, EncryptionKey, Key, KeyGenerator, OkamotoUchiyamaSecretKey1536, OpeningKey};
+    use crypto_bigint::{NonZero, U512};
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn should_generate_random_key() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = OkamotoUchiyamaSecretKey1536::random(&mut rng);
+
+        assert!(pk.n().bits() > 3 * U512::BITS - 3);
+        let m = pk.random_plaintext(&mut rng);
+        let (c, u) = pk.encrypt(&m, &mut rng);
+        assert_eq!(sk.decrypt(&c), m);
+        assert_eq!(sk.open(&c), (m, u));
+    }
+
+    #[test]
+    fn should_reject_invalid_plaintext_and_ciphertext() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = OkamotoUchiyamaSecretKey1536::random(&mut rng);
+
+        assert!(bool::from(pk.plaintext_is_valid(&U512::ONE.shl_vartime(510))));
+        assert!(!bool::from(pk.plaintext_is_valid(&U512::ONE.shl_vartime(511))));
+
+        let c = NonZero::new(*pk.n().as_ref()).unwrap();
+        assert!(bool::from(sk.try_decrypt(&c).is_none()));
+    }
+}
@@ -0,0 +1,352 @@
+use crate::pk::PublicKey;
+use crate::sk::SecretKey;
+use crate::traits::{DecryptionKey, EncryptionKey, Key};
+use crate::utils::{uint_from_be_bytes, uint_to_be_bytes, wide_div, wide_rem};
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, Split, Uint};
+use rand_core::CryptoRng;
+use std::fmt;
+
+// single server pir: every row is length prefixed, zero padded and cut into plaintext chunks; the client sends
+// encryptions of a selection vector e_i and the server answers prod_i c_i^(db_i) per chunk, an encryption of the
+// selected chunk; the square layout arranges the rows in a grid, selects the column first, splits every resulting
+// ciphertext into two plaintexts c = hi n + lo and selects the row of the grid over those, the client decrypts twice
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PirError {
+    EmptyDatabase,
+    RowTooLong(usize),
+    IndexOutOfRange,
+    LengthMismatch,
+    InvalidCiphertext(usize),
+    MalformedRow,
+}
+
+impl fmt::Display for PirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PirError::EmptyDatabase => write!(f, "database has no rows"),
+            PirError::RowTooLong(i) => write!(f, "row {i} is too long"),
+            PirError::IndexOutOfRange => write!(f, "index is out of range"),
+            PirError::LengthMismatch => write!(f, "message does not match the database layout"),
+            PirError::InvalidCiphertext(i) => write!(f, "ciphertext {i} is not valid for the key"),
+            PirError::MalformedRow => write!(f, "decoded row is malformed"),
+        }
+    }
+}
+
+impl std::error::Error for PirError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PirLayout {
+    Linear,
+    Square,
+}
+
+impl PirLayout {
+    // lengths of the selection vectors, innermost first
+    fn dimensions(&self, rows: usize) -> Vec<usize> {
+        match self {
+            PirLayout::Linear => vec![rows],
+            PirLayout::Square => {
+                let width = rows.isqrt() + usize::from(rows.isqrt().pow(2) < rows);
+                vec![width, rows.div_ceil(width)]
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PirDatabase<const S: usize> {
+    rows: Vec<Vec<Uint<S>>>,
+    chunks: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct PirQuery<const D: usize> {
+    pub selections: Vec<Vec<NonZero<Uint<D>>>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PirResponse<const D: usize> {
+    pub chunks: Vec<NonZero<Uint<D>>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PirClient<'a, const H: usize, const S: usize, const D: usize> {
+    sk: &'a SecretKey<H, S, D>,
+    rows: usize,
+    layout: PirLayout,
+}
+
+#[derive(Debug, Clone)]
+pub struct PirServer<const S: usize, const D: usize> {
+    key: PublicKey<S, D>,
+    database: PirDatabase<S>,
+}
+
+fn chunk_bytes<const S: usize>(n: &Odd<Uint<S>>) -> usize {
+    (n.bits() as usize - 1) / 8
+}
+
+impl<const S: usize> PirDatabase<S> {
+    pub fn new<const D: usize, R: AsRef<[u8]>>(key: &PublicKey<S, D>, rows: &[R]) -> Result<Self, PirError> {
+        if rows.is_empty() {
+            return Err(PirError::EmptyDatabase);
+        }
+        if let Some(i) = rows.iter().position(|row| u32::try_from(row.as_ref().len()).is_err()) {
+            return Err(PirError::RowTooLong(i));
+        }
+
+        let chunk_bytes = chunk_bytes(&key.n);
+        let payload = 4 + rows.iter().map(|row| row.as_ref().len()).max().unwrap_or(0);
+        let chunks = payload.div_ceil(chunk_bytes);
+
+        let rows = rows
+            .iter()
+            .map(|row| {
+                let row = row.as_ref();
+                let mut bytes = Vec::with_capacity(chunks * chunk_bytes);
+                bytes.extend_from_slice(&(row.len() as u32).to_be_bytes());
+                bytes.extend_from_slice(row);
+                bytes.resize(chunks * chunk_bytes, 0);
+                bytes
+                    .chunks(chunk_bytes)
+                    .map(|chunk| uint_from_be_bytes(chunk).expect("chunk is shorter than the modulus"))
+                    .collect()
+            })
+            .collect();
+
+        Ok(PirDatabase { rows, chunks })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn chunks_per_row(&self) -> usize {
+        self.chunks
+    }
+}
+
+impl<
+    'a,
+    const H: usize,
+    const H_UNSAT: usize,
+    const S: usize,
+    const S_UNSAT: usize,
+    const D: usize,
+    const D_UNSAT: usize,
+    const Q: usize,
+> PirClient<'a, H, S, D>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn new(sk: &'a SecretKey<H, S, D>, rows: usize, layout: PirLayout) -> Result<Self, PirError> {
+        if rows == 0 {
+            return Err(PirError::EmptyDatabase);
+        }
+
+        Ok(PirClient { sk, rows, layout })
+    }
+
+    pub fn query<R: CryptoRng + ?Sized>(&self, index: usize, rng: &mut R) -> Result<PirQuery<D>, PirError> {
+        if index >= self.rows {
+            return Err(PirError::IndexOutOfRange);
+        }
+
+        let pk = &self.sk.pk;
+        let mut remaining = index;
+        let selections = self
+            .layout
+            .dimensions(self.rows)
+            .into_iter()
+            .map(|length| {
+                let selected = remaining % length;
+                remaining /= length;
+                (0..length)
+                    .map(|i| pk.encrypt(&Uint::from_u8((i == selected) as u8), rng).0)
+                    .collect()
+            })
+            .collect();
+
+        Ok(PirQuery { selections })
+    }
+
+    pub fn decode(&self, response: &PirResponse<D>) -> Result<Vec<u8>, PirError> {
+        let pk = &self.sk.pk;
+        let decrypt = |i: usize, c: &NonZero<Uint<D>>| {
+            Option::<Uint<S>>::from(self.sk.try_decrypt(c)).ok_or(PirError::InvalidCiphertext(i))
+        };
+
+        let chunks = match self.layout {
+            PirLayout::Linear => response
+                .chunks
+                .iter()
+                .enumerate()
+                .map(|(i, c)| decrypt(i, c))
+                .collect::<Result<Vec<_>, _>>()?,
+            PirLayout::Square => {
+                if response.chunks.len() % 2 != 0 {
+                    return Err(PirError::LengthMismatch);
+                }
+                let mut chunks = Vec::with_capacity(response.chunks.len() / 2);
+                for (i, pair) in response.chunks.chunks(2).enumerate() {
+                    // reassemble the inner ciphertext hi n + lo, then decrypt it
+                    let lo = decrypt(2 * i, &pair[0])?;
+                    let hi = decrypt(2 * i + 1, &pair[1])?;
+                    let inner = hi
+                        .concat(&Uint::ZERO)
+                        .wrapping_mul(&pk.n.as_ref().concat(&Uint::ZERO))
+                        .wrapping_add(&lo.concat(&Uint::ZERO));
+                    let inner =
+                        Option::<NonZero<Uint<D>>>::from(inner.to_nz()).ok_or(PirError::InvalidCiphertext(2 * i))?;
+                    chunks.push(decrypt(2 * i, &inner)?);
+                }
+                chunks
+            }
+        };
+
+        let chunk_bytes = chunk_bytes(&pk.n);
+        let mut bytes = Vec::with_capacity(chunks.len() * chunk_bytes);
+        for chunk in &chunks {
+            let encoded = uint_to_be_bytes(chunk);
+            if encoded[..encoded.len() - chunk_bytes].iter().any(|&b| b != 0) {
+                return Err(PirError::MalformedRow);
+            }
+            bytes.extend_from_slice(&encoded[encoded.len() - chunk_bytes..]);
+        }
+
+        let length = bytes
+            .first_chunk::<4>()
+            .map(|prefix| u32::from_be_bytes(*prefix) as usize)
+            .ok_or(PirError::MalformedRow)?;
+        if 4 + length > bytes.len() {
+            return Err(PirError::MalformedRow);
+        }
+        Ok(bytes[4..4 + length].to_vec())
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> PirServer<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn new(key: PublicKey<S, D>, database: PirDatabase<S>) -> Self {
+        PirServer { key, database }
+    }
+
+    pub fn respond(&self, query: &PirQuery<D>) -> Result<PirResponse<D>, PirError> {
+        let key = &self.key;
+        let rows = self.database.rows();
+        let layout = match query.selections.len() {
+            1 => PirLayout::Linear,
+            2 => PirLayout::Square,
+            _ => return Err(PirError::LengthMismatch),
+        };
+        let dimensions = layout.dimensions(rows);
+        if query.selections.iter().map(Vec::len).ne(dimensions.iter().copied()) {
+            return Err(PirError::LengthMismatch);
+        }
+        for (i, c) in query.selections.iter().flatten().enumerate() {
+            if !bool::from(key.ciphertext_is_valid(c)) {
+                return Err(PirError::InvalidCiphertext(i));
+            }
+        }
+
+        // one multi-exponentiation per chunk and row of the grid over the innermost selection vector
+        let width = dimensions[0];
+        let selected: Vec<Vec<NonZero<Uint<D>>>> = (0..rows.div_ceil(width))
+            .map(|a| {
+                let rows = &self.database.rows[a * width..rows.min((a + 1) * width)];
+                (0..self.database.chunks)
+                    .map(|j| {
+                        let terms: Vec<_> = query.selections[0]
+                            .iter()
+                            .zip(rows)
+                            .map(|(c, row)| (*c, row[j]))
+                            .collect();
+                        key.ciphertext_multi_mul_scalar(&terms)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let chunks = match layout {
+            PirLayout::Linear => selected.into_iter().next().expect("database is not empty"),
+            PirLayout::Square => {
+                let n = key.n.as_nz_ref();
+                let mut chunks = Vec::with_capacity(2 * self.database.chunks);
+                for j in 0..self.database.chunks {
+                    let (lo, hi): (Vec<_>, Vec<_>) = query.selections[1]
+                        .iter()
+                        .zip(&selected)
+                        .map(|(c, row)| ((*c, wide_rem(&row[j], n)), (*c, wide_div(&row[j], n))))
+                        .unzip();
+                    chunks.push(key.ciphertext_multi_mul_scalar(&lo));
+                    chunks.push(key.ciphertext_multi_mul_scalar(&hi));
+                }
+                chunks
+            }
+        };
+
+        Ok(PirResponse { chunks })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::KeyGenerator;
+    use crate::pir::{PirClient, PirDatabase, PirError, PirLayout, PirServer};
+    use crate::sk::SecretKey;
+    use crypto_bigint::{U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+
+    fn rows() -> Vec<Vec<u8>> {
+        (0..10u8).map(|i| vec![i; 20 * i as usize]).collect()
+    }
+
+    #[test]
+    fn should_retrieve_row_linear() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let rows = rows();
+        let server = PirServer::new(pk, PirDatabase::new(&pk, &rows).unwrap());
+        let client = PirClient::new(&sk, rows.len(), PirLayout::Linear).unwrap();
+
+        for index in [0, 4, 9] {
+            let response = server.respond(&client.query(index, &mut rng).unwrap()).unwrap();
+            assert_eq!(client.decode(&response).unwrap(), rows[index]);
+        }
+        assert_eq!(client.query(10, &mut rng).unwrap_err(), PirError::IndexOutOfRange);
+    }
+
+    #[test]
+    fn should_retrieve_row_square() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let rows = rows();
+        let server = PirServer::new(pk, PirDatabase::new(&pk, &rows).unwrap());
+        let client = PirClient::new(&sk, rows.len(), PirLayout::Square).unwrap();
+
+        for index in [0, 3, 9] {
+            let query = client.query(index, &mut rng).unwrap();
+            assert_eq!(query.selections.iter().map(Vec::len).collect::<Vec<_>>(), vec![4, 3]);
+            let response = server.respond(&query).unwrap();
+            assert_eq!(client.decode(&response).unwrap(), rows[index]);
+        }
+    }
+}
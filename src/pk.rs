@@ -1,9 +1,26 @@
+mod encoding;
 mod encrypt;
 mod homomorphic;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "unstable-pkcs8")]
+mod pkcs8;
 mod precomp;
 mod rand;
+mod validate;
 
-use crate::pk::precomp::PublicPrecomputation;
+pub use crate::pk::encoding::{BatchError, FINGERPRINT_LEN};
+#[cfg(feature = "json")]
+pub use crate::pk::json::JsonError;
+#[cfg(feature = "unstable-pkcs8")]
+pub use crate::pk::pkcs8::ALGORITHM_OID;
+pub use crate::pk::validate::{ValidationPolicy, ValidationReport};
+
+#[cfg(feature = "json")]
+pub(crate) use crate::pk::json::{KEY_TYPE, PublicJwk, decode_integer, encode_integer};
+#[cfg(feature = "unstable-pkcs8")]
+pub(crate) use crate::pk::pkcs8::ALGORITHM_ID;
+pub(crate) use crate::pk::precomp::PublicPrecomputation;
 use crypto_bigint::{Concat, Odd, Split, Uint};
 
 #[derive(Debug, Copy, Clone)]
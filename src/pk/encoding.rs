@@ -0,0 +1,204 @@
+use crate::pk::PublicKey;
+use crate::traits::Key;
+use crate::utils::uint_to_be_bytes;
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, Split, Uint};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use subtle::{Choice, CtOption};
+
+// batch frame: version (1 byte) | flags (1 byte) | ciphertext width (4 bytes) | count (4 bytes) | [fingerprint] | ciphertexts
+const BATCH_VERSION: u8 = 1;
+const BATCH_FLAG_FINGERPRINT: u8 = 0x01;
+const BATCH_HEADER_LEN: usize = 10;
+
+pub const FINGERPRINT_LEN: usize = 32;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BatchError {
+    Truncated,
+    UnsupportedVersion(u8),
+    UnsupportedFlags(u8),
+    WidthMismatch,
+    FingerprintMismatch,
+    InvalidCiphertext(usize),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Truncated => write!(f, "batch length does not match its header"),
+            BatchError::UnsupportedVersion(v) => write!(f, "unsupported batch version {v}"),
+            BatchError::UnsupportedFlags(flags) => write!(f, "unsupported batch flags {flags:#04x}"),
+            BatchError::WidthMismatch => write!(f, "ciphertext width does not match the key size"),
+            BatchError::FingerprintMismatch => write!(f, "batch was encrypted under a different key"),
+            BatchError::InvalidCiphertext(i) => write!(f, "ciphertext {i} is not valid for the key"),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> PublicKey<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn fingerprint(&self) -> [u8; FINGERPRINT_LEN] {
+        Sha256::digest(uint_to_be_bytes(self.n.as_ref())).into()
+    }
+
+    pub fn ciphertext_to_bytes(&self, c: &NonZero<Uint<D>>) -> Vec<u8> {
+        uint_to_be_bytes(c.as_ref())
+    }
+
+    pub fn ciphertext_from_bytes(&self, bytes: &[u8]) -> CtOption<NonZero<Uint<D>>> {
+        let c = fixed_width_from_be_bytes::<D>(bytes);
+        let is_valid = c.is_some() & self.ciphertext_is_valid(&c.unwrap_or(NonZero::ONE));
+
+        CtOption::new(c.unwrap_or(NonZero::ONE), is_valid)
+    }
+
+    pub fn nonce_to_bytes(&self, r: &NonZero<Uint<S>>) -> Vec<u8> {
+        uint_to_be_bytes(r.as_ref())
+    }
+
+    pub fn nonce_from_bytes(&self, bytes: &[u8]) -> CtOption<NonZero<Uint<S>>> {
+        let r = fixed_width_from_be_bytes::<S>(bytes);
+        let is_valid = r.is_some() & self.nonce_is_valid(&r.unwrap_or(NonZero::ONE));
+
+        CtOption::new(r.unwrap_or(NonZero::ONE), is_valid)
+    }
+
+    pub fn ciphertext_batch_to_bytes(&self, cs: &[NonZero<Uint<D>>], with_fingerprint: bool) -> Vec<u8> {
+        let count = u32::try_from(cs.len()).expect("batch is too large");
+        let mut bytes = Vec::with_capacity(BATCH_HEADER_LEN + FINGERPRINT_LEN + cs.len() * Uint::<D>::BYTES);
+        bytes.push(BATCH_VERSION);
+        bytes.push(if with_fingerprint { BATCH_FLAG_FINGERPRINT } else { 0 });
+        bytes.extend_from_slice(&(Uint::<D>::BYTES as u32).to_be_bytes());
+        bytes.extend_from_slice(&count.to_be_bytes());
+        if with_fingerprint {
+            bytes.extend_from_slice(&self.fingerprint());
+        }
+        for c in cs {
+            bytes.extend_from_slice(&self.ciphertext_to_bytes(c));
+        }
+
+        bytes
+    }
+
+    pub fn ciphertext_batch_from_bytes(&self, bytes: &[u8]) -> Result<Vec<NonZero<Uint<D>>>, BatchError> {
+        let (header, mut body) = bytes.split_at_checked(BATCH_HEADER_LEN).ok_or(BatchError::Truncated)?;
+        if header[0] != BATCH_VERSION {
+            return Err(BatchError::UnsupportedVersion(header[0]));
+        }
+        if header[1] & !BATCH_FLAG_FINGERPRINT != 0 {
+            return Err(BatchError::UnsupportedFlags(header[1]));
+        }
+        let width = u32::from_be_bytes(header[2..6].try_into().expect("header has 4 bytes of width")) as usize;
+        if width != Uint::<D>::BYTES {
+            return Err(BatchError::WidthMismatch);
+        }
+        let count = u32::from_be_bytes(header[6..10].try_into().expect("header has 4 bytes of count")) as usize;
+
+        if header[1] & BATCH_FLAG_FINGERPRINT != 0 {
+            let (fingerprint, rest) = body.split_at_checked(FINGERPRINT_LEN).ok_or(BatchError::Truncated)?;
+            if fingerprint != self.fingerprint() {
+                return Err(BatchError::FingerprintMismatch);
+            }
+            body = rest;
+        }
+        if Some(body.len()) != count.checked_mul(width) {
+            return Err(BatchError::Truncated);
+        }
+
+        body.chunks_exact(width)
+            .enumerate()
+            .map(|(i, chunk)| Option::from(self.ciphertext_from_bytes(chunk)).ok_or(BatchError::InvalidCiphertext(i)))
+            .collect()
+    }
+}
+
+fn fixed_width_from_be_bytes<const L: usize>(bytes: &[u8]) -> CtOption<NonZero<Uint<L>>> {
+    // only the exact width is canonical, shorter or longer encodings are rejected
+    if bytes.len() != Uint::<L>::BYTES {
+        return CtOption::new(NonZero::ONE, Choice::from(0));
+    }
+
+    NonZero::new(Uint::from_be_slice(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pk::encoding::{BATCH_HEADER_LEN, BatchError};
+    use crate::{DecryptionKey, EncryptionKey, KeyGenerator, PaillierSecretKey2048};
+    use crypto_bigint::U4096;
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn should_encode_and_decode_ciphertext_and_nonce() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (_, pk) = PaillierSecretKey2048::random(&mut rng);
+
+        let m = pk.random_plaintext(&mut rng);
+        let (c, r) = pk.encrypt(&m, &mut rng);
+
+        let c_bytes = pk.ciphertext_to_bytes(&c);
+        let r_bytes = pk.nonce_to_bytes(&r);
+        assert_eq!(c_bytes.len(), U4096::BYTES);
+        assert_eq!(r_bytes.len(), U4096::BYTES / 2);
+        assert_eq!(pk.ciphertext_from_bytes(&c_bytes).unwrap(), c);
+        assert_eq!(pk.nonce_from_bytes(&r_bytes).unwrap(), r);
+
+        assert!(bool::from(pk.ciphertext_from_bytes(&c_bytes[1..]).is_none()));
+        assert!(bool::from(pk.ciphertext_from_bytes(&[0xff; U4096::BYTES]).is_none()));
+        assert!(bool::from(pk.ciphertext_from_bytes(&[0x00; U4096::BYTES]).is_none()));
+        assert!(bool::from(pk.nonce_from_bytes(&[0xff; U4096::BYTES / 2]).is_none()));
+    }
+
+    #[test]
+    fn should_encode_and_decode_batch() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = PaillierSecretKey2048::random(&mut rng);
+        let (_, other_pk) = PaillierSecretKey2048::random(&mut rng);
+
+        let ms: Vec<_> = (0..4).map(|_| pk.random_plaintext(&mut rng)).collect();
+        let cs: Vec<_> = ms.iter().map(|m| pk.encrypt(m, &mut rng).0).collect();
+
+        for with_fingerprint in [false, true] {
+            let bytes = pk.ciphertext_batch_to_bytes(&cs, with_fingerprint);
+            let decoded = pk.ciphertext_batch_from_bytes(&bytes).unwrap();
+            assert_eq!(decoded, cs);
+            assert_eq!(decoded.iter().map(|c| sk.decrypt(c)).collect::<Vec<_>>(), ms);
+
+            assert_eq!(
+                pk.ciphertext_batch_from_bytes(&bytes[..bytes.len() - 1]),
+                Err(BatchError::Truncated)
+            );
+        }
+
+        let bytes = pk.ciphertext_batch_to_bytes(&cs, true);
+        assert_eq!(
+            other_pk.ciphertext_batch_from_bytes(&bytes),
+            Err(BatchError::FingerprintMismatch)
+        );
+
+        let mut bytes = pk.ciphertext_batch_to_bytes(&cs, false);
+        bytes[0] = 2;
+        assert_eq!(
+            pk.ciphertext_batch_from_bytes(&bytes),
+            Err(BatchError::UnsupportedVersion(2))
+        );
+
+        let mut bytes = pk.ciphertext_batch_to_bytes(&cs, false);
+        bytes[BATCH_HEADER_LEN + U4096::BYTES..BATCH_HEADER_LEN + 2 * U4096::BYTES].fill(0xff);
+        assert_eq!(
+            pk.ciphertext_batch_from_bytes(&bytes),
+            Err(BatchError::InvalidCiphertext(1))
+        );
+    }
+}
@@ -2,7 +2,7 @@ use crate::pk::PublicKey;
 use crate::traits::HomomorphicKey;
 use crate::utils::nz_mul_mod;
 use crypto_bigint::modular::{MontyForm, SafeGcdInverter};
-use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, Split, Uint};
+use crypto_bigint::{Concat, MultiExponentiate, NonZero, Odd, PrecomputeInverter, Split, Uint};
 use subtle::{Choice, ConstantTimeEq, ConstantTimeLess};
 
 impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> HomomorphicKey<Uint<S>>
@@ -84,10 +84,33 @@ where
     }
 }
 
+impl<const S: usize, const D: usize, const Q: usize> PublicKey<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Uint<D>: Concat<Output = Uint<Q>> + Split<Output = Uint<S>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    // c_1^s_1 * ... * c_k^s_k sharing the squarings, an encryption of s_1 m_1 + ... + s_k m_k
+    pub fn ciphertext_multi_mul_scalar(&self, terms: &[(NonZero<Uint<D>>, Uint<S>)]) -> NonZero<Uint<D>> {
+        if terms.is_empty() {
+            return NonZero::ONE;
+        }
+
+        let terms: Vec<_> = terms
+            .iter()
+            .map(|(c, s)| (MontyForm::new(c, self.precomputation.nn_monty_params), *s))
+            .collect();
+        MontyForm::multi_exponentiate(terms.as_slice())
+            .retrieve()
+            .to_nz()
+            .expect("c is non zero")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::traits::HomomorphicKey;
-    use crate::{EncryptionKey, KeyGenerator, OpeningKey, PaillierSecretKey2048};
+    use crate::{DecryptionKey, EncryptionKey, KeyGenerator, OpeningKey, PaillierSecretKey2048};
     use crypto_bigint::{RandomMod, Uint};
     use rand_chacha::ChaCha8Rng;
     use rand_core::SeedableRng;
@@ -198,4 +221,25 @@ mod tests {
         assert_eq!(m, m_actual);
         assert_eq!(r, r_actual);
     }
+
+    #[test]
+    fn should_homomorphic_multi_mul_scalar() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = PaillierSecretKey2048::random(&mut rng);
+
+        let terms: Vec<_> = (0..3)
+            .map(|_| {
+                let m = pk.random_plaintext(&mut rng);
+                let s = Uint::random_mod(&mut rng, pk.n.as_nz_ref());
+                (m, s, pk.encrypt(&m, &mut rng).0)
+            })
+            .collect();
+        let m = terms.iter().fold(Uint::ZERO, |acc, (m, s, _)| {
+            acc.add_mod(&m.mul_mod(s, pk.n.as_nz_ref()), &pk.n)
+        });
+        let c = pk.ciphertext_multi_mul_scalar(&terms.iter().map(|(_, s, c)| (*c, *s)).collect::<Vec<_>>());
+
+        assert_eq!(sk.decrypt(&c), m);
+        assert_eq!(sk.decrypt(&pk.ciphertext_multi_mul_scalar(&[])), Uint::ZERO);
+    }
 }
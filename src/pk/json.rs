@@ -0,0 +1,200 @@
+use crate::pk::PublicKey;
+use crate::traits::Key;
+use crate::utils::{uint_from_be_bytes, uint_to_be_bytes};
+use base64ct::{Base64UrlUnpadded, Encoding};
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, Split, Uint};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// JSON Web Key style formats used by python-paillier and javallier, integers are base64url encoded big-endian.
+pub(crate) const KEY_TYPE: &str = "DAJ";
+const ALGORITHM: &str = "PAI-GN1";
+
+#[derive(Debug)]
+pub enum JsonError {
+    Json(serde_json::Error),
+    UnsupportedKeyType,
+    UnsupportedAlgorithm,
+    InvalidInteger,
+    InvalidKey,
+    InvalidCiphertext,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::Json(e) => write!(f, "malformed JSON: {e}"),
+            JsonError::UnsupportedKeyType => write!(f, "unsupported key type, expected \"{KEY_TYPE}\""),
+            JsonError::UnsupportedAlgorithm => write!(f, "unsupported algorithm, expected \"{ALGORITHM}\""),
+            JsonError::InvalidInteger => write!(f, "integer is not valid base64url or is too large"),
+            JsonError::InvalidKey => write!(f, "key parameters are inconsistent"),
+            JsonError::InvalidCiphertext => write!(f, "ciphertext is not valid for the key"),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+impl From<serde_json::Error> for JsonError {
+    fn from(e: serde_json::Error) -> Self {
+        JsonError::Json(e)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PublicJwk {
+    pub(crate) kty: String,
+    pub(crate) alg: String,
+    #[serde(default)]
+    pub(crate) key_ops: Vec<String>,
+    pub(crate) n: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedNumberJson {
+    v: String,
+    e: i32,
+}
+
+pub(crate) fn encode_integer<const L: usize>(x: &Uint<L>) -> String {
+    let bytes = uint_to_be_bytes(x);
+    let leading_zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    Base64UrlUnpadded::encode_string(&bytes[leading_zeros..])
+}
+
+pub(crate) fn decode_integer<const L: usize>(encoded: &str) -> Result<Uint<L>, JsonError> {
+    // python-paillier strips the padding but be lenient about it
+    let bytes = Base64UrlUnpadded::decode_vec(encoded.trim_end_matches('=')).map_err(|_| JsonError::InvalidInteger)?;
+    uint_from_be_bytes(&bytes).ok_or(JsonError::InvalidInteger)
+}
+
+impl<const S: usize, const D: usize, const Q: usize> PublicKey<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn to_jwk(&self) -> String {
+        serde_json::to_string(&self.public_jwk()).expect("JWK is serializable")
+    }
+
+    pub fn from_jwk(json: &str) -> Result<Self, JsonError> {
+        Self::from_public_jwk(&serde_json::from_str(json)?)
+    }
+
+    pub(crate) fn public_jwk(&self) -> PublicJwk {
+        PublicJwk {
+            kty: KEY_TYPE.into(),
+            alg: ALGORITHM.into(),
+            key_ops: vec!["encrypt".into()],
+            n: encode_integer(self.n.as_ref()),
+        }
+    }
+
+    pub(crate) fn from_public_jwk(jwk: &PublicJwk) -> Result<Self, JsonError> {
+        if jwk.kty != KEY_TYPE {
+            return Err(JsonError::UnsupportedKeyType);
+        }
+        if jwk.alg != ALGORITHM {
+            return Err(JsonError::UnsupportedAlgorithm);
+        }
+
+        let n: Odd<Uint<S>> = Option::from(decode_integer::<S>(&jwk.n)?.to_odd()).ok_or(JsonError::InvalidKey)?;
+        Ok(PublicKey::from_n_unchecked(n))
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> PublicKey<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn ciphertext_to_json(&self, c: &NonZero<Uint<D>>, exponent: i32) -> String {
+        let encrypted_number = EncryptedNumberJson {
+            v: encode_integer(c.as_ref()),
+            e: exponent,
+        };
+
+        serde_json::to_string(&encrypted_number).expect("encrypted number is serializable")
+    }
+
+    pub fn ciphertext_from_json(&self, json: &str) -> Result<(NonZero<Uint<D>>, i32), JsonError> {
+        let encrypted_number: EncryptedNumberJson = serde_json::from_str(json)?;
+        let c: NonZero<Uint<D>> =
+            Option::from(decode_integer::<D>(&encrypted_number.v)?.to_nz()).ok_or(JsonError::InvalidCiphertext)?;
+        if !bool::from(self.ciphertext_is_valid(&c)) {
+            return Err(JsonError::InvalidCiphertext);
+        }
+
+        Ok((c, encrypted_number.e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pk::json::{JsonError, decode_integer, encode_integer};
+    use crate::{DecryptionKey, EncryptionKey, KeyGenerator, PaillierPublicKey2048, PaillierSecretKey2048};
+    use crypto_bigint::{U64, U2048};
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn should_encode_integers_as_minimal_base64url() {
+        assert_eq!(encode_integer(&U64::from(65537u32)), "AQAB");
+        assert_eq!(decode_integer::<{ U64::LIMBS }>("AQAB").unwrap(), U64::from(65537u32));
+        assert_eq!(
+            decode_integer::<{ U64::LIMBS }>("AAEAAQ==").unwrap(),
+            U64::from(65537u32)
+        );
+        assert!(matches!(
+            decode_integer::<{ U64::LIMBS }>("AQIDBAUGBwgJ"),
+            Err(JsonError::InvalidInteger)
+        ));
+    }
+
+    #[test]
+    fn should_import_python_paillier_public_key() {
+        let json =
+            r#"{"kty": "DAJ", "alg": "PAI-GN1", "key_ops": ["encrypt"], "n": "AQAB", "kid": "Paillier public key"}"#;
+
+        let pk = PaillierPublicKey2048::from_jwk(json).unwrap();
+        assert_eq!(pk.n.as_ref(), &U2048::from(65537u32));
+    }
+
+    #[test]
+    fn should_reject_unknown_key_type() {
+        let json = r#"{"kty": "RSA", "alg": "PAI-GN1", "n": "AQAB"}"#;
+
+        assert!(matches!(
+            PaillierPublicKey2048::from_jwk(json),
+            Err(JsonError::UnsupportedKeyType)
+        ));
+    }
+
+    #[test]
+    fn should_encode_and_decode_public_key_and_ciphertext() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = PaillierSecretKey2048::random(&mut rng);
+
+        let decoded_pk = PaillierPublicKey2048::from_jwk(&pk.to_jwk()).unwrap();
+        assert_eq!(decoded_pk.n, pk.n);
+
+        let m = pk.random_plaintext(&mut rng);
+        let (c, _) = decoded_pk.encrypt(&m, &mut rng);
+        let (decoded_c, exponent) = pk.ciphertext_from_json(&pk.ciphertext_to_json(&c, -3)).unwrap();
+        assert_eq!(decoded_c, c);
+        assert_eq!(exponent, -3);
+        assert_eq!(sk.decrypt(&decoded_c), m);
+
+        let c_too_big = encode_integer(pk.precomputation.nn_monty_params.modulus());
+        let json = format!(r#"{{"v": "{c_too_big}", "e": 0}}"#);
+        assert!(matches!(
+            pk.ciphertext_from_json(&json),
+            Err(JsonError::InvalidCiphertext)
+        ));
+    }
+}
@@ -0,0 +1,228 @@
+use crate::pk::PublicKey;
+use crate::utils::small_primes;
+use crypto_bigint::modular::{MontyForm, SafeGcdInverter};
+use crypto_bigint::{Concat, Integer, Limb, NonZero, Odd, PrecomputeInverter, Split, U64, Uint};
+
+#[derive(Debug, Copy, Clone)]
+pub struct ValidationPolicy<'a, const S: usize> {
+    pub trial_division_bound: u32,
+    pub known_bad_moduli: &'a [Uint<S>],
+    pub pollard_pm1_bound: u32,
+    pub fermat_iterations: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ValidationReport<const S: usize> {
+    pub bits: u32,
+    pub expected_length: bool,
+    pub odd: bool,
+    pub non_square: bool,
+    pub small_factor: Option<u32>,
+    pub shared_factor_with: Option<usize>,
+    pub pollard_pm1_factor: Option<Uint<S>>,
+    pub fermat_factor: Option<Uint<S>>,
+}
+
+impl<const S: usize> Default for ValidationPolicy<'_, S> {
+    fn default() -> Self {
+        ValidationPolicy {
+            trial_division_bound: 1 << 16,
+            known_bad_moduli: &[],
+            pollard_pm1_bound: 0,
+            fermat_iterations: 0,
+        }
+    }
+}
+
+impl<const S: usize> ValidationReport<S> {
+    pub fn is_valid(&self) -> bool {
+        self.expected_length
+            && self.odd
+            && self.non_square
+            && self.small_factor.is_none()
+            && self.shared_factor_with.is_none()
+            && self.pollard_pm1_factor.is_none()
+            && self.fermat_factor.is_none()
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const Q: usize> PublicKey<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn validate(&self, policy: &ValidationPolicy<S>) -> ValidationReport<S> {
+        let n = self.n.as_ref();
+        let primes = small_primes(policy.trial_division_bound.max(policy.pollard_pm1_bound));
+
+        ValidationReport {
+            bits: n.bits(),
+            // the product of two primes of half the size has either full or one bit shorter length
+            expected_length: n.bits() >= Uint::<S>::BITS - 1,
+            odd: n.is_odd().into(),
+            non_square: !is_square(n),
+            small_factor: primes
+                .iter()
+                .take_while(|&&l| l <= policy.trial_division_bound)
+                .find(|&&l| n != &Uint::from(l) && n.rem_limb(NonZero::new(Limb::from(l)).unwrap()) == Limb::ZERO)
+                .copied(),
+            shared_factor_with: policy.known_bad_moduli.iter().position(|m| n.gcd(m) != Uint::ONE),
+            pollard_pm1_factor: self.pollard_pm1(&primes, policy.pollard_pm1_bound),
+            fermat_factor: self.fermat(policy.fermat_iterations),
+        }
+    }
+
+    fn pollard_pm1(&self, primes: &[u32], bound: u32) -> Option<Uint<S>> {
+        if bound < 2 {
+            return None;
+        }
+
+        // a = 2^M where M is the product of all maximal prime powers not exceeding the bound
+        let mut a = MontyForm::new(&Uint::from(2u32), self.precomputation.n_monty_params);
+        for &l in primes.iter().take_while(|&&l| l <= bound) {
+            let mut l_power = l as u64;
+            while l_power * (l as u64) <= bound as u64 {
+                l_power *= l as u64;
+            }
+            a = a.pow(&U64::from(l_power));
+        }
+
+        let g = a.retrieve().wrapping_sub(&Uint::ONE).gcd(&self.n);
+        (g != Uint::ONE && &g != self.n.as_ref()).then_some(g)
+    }
+
+    fn fermat(&self, iterations: u32) -> Option<Uint<S>> {
+        let n = self.n.as_ref();
+        let mut a = n.sqrt_vartime();
+        if &a.wrapping_square() != n {
+            a = a.wrapping_add(&Uint::ONE);
+        }
+
+        // a^2 - n is small when p and q are close, so wrapping arithmetic yields its exact value
+        for _ in 0..iterations {
+            let b2 = a.wrapping_square().wrapping_sub(n);
+            if is_square(&b2) {
+                let factor = a.wrapping_sub(&b2.sqrt_vartime());
+                return (factor != Uint::ONE).then_some(factor);
+            }
+            a = a.wrapping_add(&Uint::ONE);
+        }
+
+        None
+    }
+}
+
+fn is_square<const S: usize>(x: &Uint<S>) -> bool {
+    &x.sqrt_vartime().wrapping_square() == x
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pk::PublicKey;
+    use crate::pk::validate::ValidationPolicy;
+    use crate::utils::small_primes;
+    use crate::{KeyGenerator, PaillierSecretKey2048};
+    use crypto_bigint::{U256, U512, U1024, Uint};
+    use crypto_primes::RandomPrimeWithRng;
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::{RngCore, SeedableRng};
+
+    type SmallPublicKey = PublicKey<{ U512::LIMBS }, { U1024::LIMBS }>;
+
+    fn small_public_key(p: &U256, q: &U256) -> SmallPublicKey {
+        PublicKey::from_n_unchecked(p.widening_mul(q).to_odd().unwrap())
+    }
+
+    fn strict_policy<const S: usize>(known_bad_moduli: &[Uint<S>]) -> ValidationPolicy<'_, S> {
+        ValidationPolicy {
+            trial_division_bound: 1 << 16,
+            known_bad_moduli,
+            pollard_pm1_bound: 1 << 12,
+            fermat_iterations: 1 << 10,
+        }
+    }
+
+    #[test]
+    fn should_accept_random_key() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (_, pk) = PaillierSecretKey2048::random(&mut rng);
+        let (_, other_pk) = PaillierSecretKey2048::random(&mut rng);
+
+        let report = pk.validate(&strict_policy(&[*other_pk.n.as_ref()]));
+        assert!(report.is_valid(), "{report:?}");
+    }
+
+    #[test]
+    fn should_reject_small_factor() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let p = U256::generate_prime_with_rng(&mut rng, 256);
+        let q = U256::from(65521u32);
+
+        let report = small_public_key(&p, &q).validate(&ValidationPolicy::default());
+        assert!(!report.expected_length);
+        assert_eq!(report.small_factor, Some(65521));
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn should_reject_square() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let p = U256::generate_prime_with_rng(&mut rng, 256);
+
+        let report = small_public_key(&p, &p).validate(&ValidationPolicy::default());
+        assert!(!report.non_square);
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn should_reject_shared_factor() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let p = U256::generate_prime_with_rng(&mut rng, 256);
+        let q1 = U256::generate_prime_with_rng(&mut rng, 256);
+        let q2 = U256::generate_prime_with_rng(&mut rng, 256);
+        let bad_moduli = [U512::ONE, *small_public_key(&p, &q2).n.as_ref()];
+
+        let report = small_public_key(&p, &q1).validate(&strict_policy(&bad_moduli));
+        assert_eq!(report.shared_factor_with, Some(1));
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn should_factor_close_primes() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let p = U256::generate_prime_with_rng(&mut rng, 256);
+        let mut q = p.wrapping_add(&U256::from(2u32));
+        while !q.is_prime_with_rng(&mut rng) {
+            q = q.wrapping_add(&U256::from(2u32));
+        }
+
+        let report = small_public_key(&p, &q).validate(&strict_policy(&[]));
+        assert_eq!(report.fermat_factor, Some(p.resize()));
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn should_factor_smooth_prime() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let p = loop {
+            // p - 1 is a product of distinct primes below the bound, so it divides the exponent used by p - 1 method
+            let mut primes = small_primes(1 << 12);
+            let mut pm1 = U256::from(2u32);
+            while pm1.bits() < 240 {
+                let l = primes.swap_remove(rng.next_u32() as usize % primes.len());
+                pm1 = pm1.wrapping_mul(&U256::from(l));
+            }
+            let p = pm1.wrapping_add(&U256::ONE);
+            if p.is_prime_with_rng(&mut rng) {
+                break p;
+            }
+        };
+        let q = U256::generate_prime_with_rng(&mut rng, 256);
+
+        let report = small_public_key(&p, &q).validate(&strict_policy(&[]));
+        assert_eq!(report.pollard_pm1_factor, Some(p.resize()));
+        assert!(!report.is_valid());
+    }
+}
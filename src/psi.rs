@@ -0,0 +1,324 @@
+use crate::pk::PublicKey;
+use crate::sk::SecretKey;
+use crate::traits::{DecryptionKey, EncryptionKey, HomomorphicKey, Key};
+use crate::utils::shuffle;
+use crate::zk::Transcript;
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, Split, Uint};
+use rand_core::CryptoRng;
+use std::collections::BTreeSet;
+use std::fmt;
+
+// Freedman-Nissim-Pinkas: the client hashes its elements into buckets and sends the encrypted coefficients of
+// P_b(X) = prod (X - a) for every bucket b, padded to a fixed degree with random roots; the server answers
+// Enc(r P_b(y) + y) for each of its elements y with a fresh random r, which decrypts to y if y is a root of P_b
+// and to a random value otherwise
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PsiError {
+    InvalidParams,
+    InvalidElement,
+    BucketOverflow(usize),
+    LengthMismatch,
+    InvalidCiphertext(usize),
+}
+
+impl fmt::Display for PsiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PsiError::InvalidParams => write!(f, "number of buckets and bucket capacity must be positive"),
+            PsiError::InvalidElement => write!(f, "element is not smaller than the modulus"),
+            PsiError::BucketOverflow(b) => write!(f, "bucket {b} exceeds the bucket capacity"),
+            PsiError::LengthMismatch => write!(f, "query does not match the parameters"),
+            PsiError::InvalidCiphertext(i) => write!(f, "ciphertext {i} is not valid for the key"),
+        }
+    }
+}
+
+impl std::error::Error for PsiError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PsiParams {
+    buckets: usize,
+    bucket_capacity: usize,
+}
+
+impl PsiParams {
+    pub fn new(buckets: usize, bucket_capacity: usize) -> Result<Self, PsiError> {
+        if buckets == 0 || bucket_capacity == 0 {
+            return Err(PsiError::InvalidParams);
+        }
+
+        Ok(PsiParams {
+            buckets,
+            bucket_capacity,
+        })
+    }
+
+    pub fn buckets(&self) -> usize {
+        self.buckets
+    }
+
+    pub fn bucket_capacity(&self) -> usize {
+        self.bucket_capacity
+    }
+
+    pub fn bucket<const S: usize>(&self, element: &Uint<S>) -> usize {
+        let mut transcript = Transcript::new(b"crypto-paillier/psi-bucket");
+        transcript.append_uint(b"element", element);
+        let hash = transcript.challenge_uint::<1>(b"bucket").as_words()[0];
+        (hash % self.buckets as u64) as usize
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PsiQuery<const D: usize> {
+    // encrypted coefficients of every bucket polynomial, lowest degree first
+    pub coefficients: Vec<Vec<NonZero<Uint<D>>>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PsiResponse<const D: usize> {
+    pub evaluations: Vec<NonZero<Uint<D>>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PsiClient<'a, const H: usize, const S: usize, const D: usize> {
+    sk: &'a SecretKey<H, S, D>,
+    params: PsiParams,
+    elements: BTreeSet<Uint<S>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PsiServer<const S: usize, const D: usize> {
+    key: PublicKey<S, D>,
+    params: PsiParams,
+}
+
+// coefficients of prod (X - root) mod n, lowest degree first
+pub fn polynomial_from_roots<const S: usize, const D: usize>(roots: &[Uint<S>], n: &NonZero<Uint<S>>) -> Vec<Uint<S>>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Uint<D>: Split<Output = Uint<S>>,
+{
+    let mut coefficients = vec![Uint::ONE];
+    for root in roots {
+        let mut next = vec![Uint::ZERO; coefficients.len() + 1];
+        for (i, a) in coefficients.iter().enumerate() {
+            next[i + 1] = next[i + 1].add_mod(a, n);
+            next[i] = next[i].sub_mod(&a.mul_mod(root, n), n);
+        }
+        coefficients = next;
+    }
+    coefficients
+}
+
+// Horner's rule over encrypted coefficients, Enc(P(x)) = (...(Enc(a_k) x + Enc(a_k-1)) x + ...) + Enc(a_0)
+pub fn evaluate_encrypted<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize>(
+    key: &PublicKey<S, D>,
+    coefficients: &[NonZero<Uint<D>>],
+    x: &Uint<S>,
+) -> NonZero<Uint<D>>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    coefficients.iter().rev().fold(NonZero::ONE, |acc, c| {
+        key.ciphertext_add(&key.ciphertext_mul_scalar(&acc, x), c)
+    })
+}
+
+impl<
+    'a,
+    const H: usize,
+    const H_UNSAT: usize,
+    const S: usize,
+    const S_UNSAT: usize,
+    const D: usize,
+    const D_UNSAT: usize,
+    const Q: usize,
+> PsiClient<'a, H, S, D>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn new(sk: &'a SecretKey<H, S, D>, params: PsiParams, elements: &[Uint<S>]) -> Result<Self, PsiError> {
+        if elements.iter().any(|a| a >= sk.pk.n.as_ref()) {
+            return Err(PsiError::InvalidElement);
+        }
+
+        Ok(PsiClient {
+            sk,
+            params,
+            elements: elements.iter().copied().collect(),
+        })
+    }
+
+    pub fn query<R: CryptoRng + ?Sized>(&self, rng: &mut R) -> Result<PsiQuery<D>, PsiError> {
+        let pk = &self.sk.pk;
+        let mut buckets = vec![Vec::new(); self.params.buckets];
+        for a in &self.elements {
+            buckets[self.params.bucket(a)].push(*a);
+        }
+
+        let mut coefficients = Vec::with_capacity(buckets.len());
+        for (b, mut roots) in buckets.into_iter().enumerate() {
+            if roots.len() > self.params.bucket_capacity {
+                return Err(PsiError::BucketOverflow(b));
+            }
+            // pad to the public degree so that the query hides the bucket loads
+            while roots.len() < self.params.bucket_capacity {
+                roots.push(pk.random_plaintext(rng));
+            }
+
+            let polynomial = polynomial_from_roots(&roots, pk.n.as_nz_ref());
+            coefficients.push(polynomial.iter().map(|a| pk.encrypt(a, rng).0).collect());
+        }
+
+        Ok(PsiQuery { coefficients })
+    }
+
+    pub fn intersect(&self, response: &PsiResponse<D>) -> Result<Vec<Uint<S>>, PsiError> {
+        let mut intersection = BTreeSet::new();
+        for (i, c) in response.evaluations.iter().enumerate() {
+            let y = Option::<Uint<S>>::from(self.sk.try_decrypt(c)).ok_or(PsiError::InvalidCiphertext(i))?;
+            if self.elements.contains(&y) {
+                intersection.insert(y);
+            }
+        }
+
+        Ok(intersection.into_iter().collect())
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> PsiServer<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn new(key: PublicKey<S, D>, params: PsiParams) -> Self {
+        PsiServer { key, params }
+    }
+
+    pub fn respond<R: CryptoRng + ?Sized>(
+        &self,
+        query: &PsiQuery<D>,
+        elements: &[Uint<S>],
+        rng: &mut R,
+    ) -> Result<PsiResponse<D>, PsiError> {
+        let key = &self.key;
+        let n = key.n.as_nz_ref();
+        if query.coefficients.len() != self.params.buckets
+            || query
+                .coefficients
+                .iter()
+                .any(|bucket| bucket.len() != self.params.bucket_capacity + 1)
+        {
+            return Err(PsiError::LengthMismatch);
+        }
+        for (i, c) in query.coefficients.iter().flatten().enumerate() {
+            if !bool::from(key.ciphertext_is_valid(c)) {
+                return Err(PsiError::InvalidCiphertext(i));
+            }
+        }
+        if elements.iter().any(|y| y >= key.n.as_ref()) {
+            return Err(PsiError::InvalidElement);
+        }
+
+        let mut evaluations = Vec::with_capacity(elements.len());
+        for y in elements {
+            let mut r = Uint::ZERO;
+            while r == Uint::ZERO {
+                r = key.random_plaintext(rng);
+            }
+
+            // Enc(r P(y)) = prod Enc(a_i)^(r y^i) in a single multi-exponentiation
+            let mut terms = Vec::with_capacity(self.params.bucket_capacity + 1);
+            let mut exponent = r;
+            for c in &query.coefficients[self.params.bucket(y)] {
+                terms.push((*c, exponent));
+                exponent = exponent.mul_mod(y, n);
+            }
+            let blinded = key.ciphertext_multi_mul_scalar(&terms);
+            evaluations.push(key.ciphertext_add(&blinded, &key.encrypt(y, rng).0));
+        }
+        shuffle(&mut evaluations, rng);
+
+        Ok(PsiResponse { evaluations })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::psi::{PsiClient, PsiError, PsiParams, PsiServer, evaluate_encrypted, polynomial_from_roots};
+    use crate::sk::SecretKey;
+    use crate::{DecryptionKey, EncryptionKey, KeyGenerator};
+    use crypto_bigint::{U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+
+    #[test]
+    fn should_evaluate_encrypted_polynomial() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let n = pk.n.as_nz_ref();
+
+        let roots = [U512::from_u8(2), U512::from_u8(5), U512::from_u8(7)];
+        let polynomial = polynomial_from_roots(&roots, n);
+        let coefficients: Vec<_> = polynomial.iter().map(|a| pk.encrypt(a, &mut rng).0).collect();
+
+        assert_eq!(
+            sk.decrypt(&evaluate_encrypted(&pk, &coefficients, &roots[1])),
+            U512::ZERO
+        );
+        // (3 - 2)(3 - 5)(3 - 7) = 8
+        assert_eq!(
+            sk.decrypt(&evaluate_encrypted(&pk, &coefficients, &U512::from_u8(3))),
+            U512::from_u8(8)
+        );
+    }
+
+    #[test]
+    fn should_intersect_sets() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let params = PsiParams::new(4, 6).unwrap();
+
+        let client_set: Vec<_> = (10u64..20).map(U512::from_u64).collect();
+        let server_set: Vec<_> = (15u64..30).map(U512::from_u64).collect();
+
+        let client = PsiClient::new(&sk, params, &client_set).unwrap();
+        let query = client.query(&mut rng).unwrap();
+        let response = PsiServer::new(pk, params)
+            .respond(&query, &server_set, &mut rng)
+            .unwrap();
+        let intersection = client.intersect(&response).unwrap();
+
+        assert_eq!(intersection, (15u64..20).map(U512::from_u64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn should_reject_overflowing_bucket() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, _) = SmallSecretKey::random(&mut rng);
+        let params = PsiParams::new(1, 2).unwrap();
+
+        let client_set: Vec<_> = (0u64..3).map(U512::from_u64).collect();
+        let client = PsiClient::new(&sk, params, &client_set).unwrap();
+
+        assert_eq!(client.query(&mut rng).unwrap_err(), PsiError::BucketOverflow(0));
+    }
+}
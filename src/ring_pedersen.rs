@@ -0,0 +1,188 @@
+use crate::sk::SecretKey;
+use crate::zk::Transcript;
+use crypto_bigint::modular::{MontyForm, MontyParams, SafeGcdInverter};
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, RandomBits, RandomMod, Split, Uint};
+use crypto_primes::RandomPrimeWithRng;
+use rand_core::CryptoRng;
+use subtle::{Choice, ConstantTimeEq};
+
+// commitment randomness is sampled from [0, 2^kappa * n) so that commitments are statistically hiding
+const STATISTICAL_SECURITY_BITS: u32 = 128;
+
+#[derive(Debug, Copy, Clone)]
+pub struct RingPedersenParams<const S: usize> {
+    pub(crate) n: Odd<Uint<S>>,
+    pub(crate) s: Uint<S>,
+    pub(crate) t: Uint<S>,
+    pub(crate) monty_params: MontyParams<S>,
+}
+
+// s = t^lambda mod n, knowing phi(n) or lambda breaks the binding property of the commitments
+#[derive(Debug, Copy, Clone)]
+pub struct RingPedersenSecret<const S: usize> {
+    pub(crate) params: RingPedersenParams<S>,
+    pub(crate) phi: NonZero<Uint<S>>,
+    pub(crate) lambda: Uint<S>,
+}
+
+impl<const S: usize> RingPedersenParams<S> {
+    pub fn from_parts_unchecked(n: Odd<Uint<S>>, s: Uint<S>, t: Uint<S>) -> Self {
+        let monty_params = MontyParams::new_vartime(n);
+
+        RingPedersenParams { n, s, t, monty_params }
+    }
+
+    pub fn n(&self) -> &Odd<Uint<S>> {
+        &self.n
+    }
+
+    pub fn s(&self) -> &Uint<S> {
+        &self.s
+    }
+
+    pub fn t(&self) -> &Uint<S> {
+        &self.t
+    }
+
+    // s^m * t^r mod n, exponents are taken as non negative integers of any width
+    pub fn commit_with_randomness<const M: usize, const R: usize>(&self, m: &Uint<M>, r: &Uint<R>) -> Uint<S> {
+        let s_to_m = MontyForm::new(&self.s, self.monty_params).pow(m);
+        let t_to_r = MontyForm::new(&self.t, self.monty_params).pow(r);
+        (s_to_m * t_to_r).retrieve()
+    }
+
+    pub fn verify_commitment<const M: usize, const R: usize>(
+        &self,
+        commitment: &Uint<S>,
+        m: &Uint<M>,
+        r: &Uint<R>,
+    ) -> Choice {
+        self.commit_with_randomness(m, r).ct_eq(commitment)
+    }
+
+    // checks s^m * t^r == a * b^e mod n
+    #[cfg(feature = "curve")]
+    pub(crate) fn commitment_eq<const M: usize, const R: usize, const E: usize>(
+        &self,
+        m: &Uint<M>,
+        r: &Uint<R>,
+        a: &Uint<S>,
+        b: &Uint<S>,
+        e: &Uint<E>,
+    ) -> Choice {
+        let lhs = self.commit_with_randomness(m, r);
+        let b_to_e = MontyForm::new(b, self.monty_params).pow(e);
+        let rhs = (MontyForm::new(a, self.monty_params) * b_to_e).retrieve();
+        lhs.ct_eq(&rhs)
+    }
+
+    pub(crate) fn append_to(&self, transcript: &mut Transcript) {
+        transcript.append_uint(b"ring-pedersen-n", self.n.as_ref());
+        transcript.append_uint(b"ring-pedersen-s", &self.s);
+        transcript.append_uint(b"ring-pedersen-t", &self.t);
+    }
+}
+
+impl<const S: usize, const D: usize> RingPedersenParams<S>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+{
+    pub fn commit<R: CryptoRng + ?Sized>(&self, m: &Uint<S>, rng: &mut R) -> (Uint<S>, Uint<D>) {
+        let r = Uint::<D>::random_bits(rng, self.n.bits() + STATISTICAL_SECURITY_BITS);
+
+        (self.commit_with_randomness(m, &r), r)
+    }
+}
+
+impl<const S: usize> RingPedersenSecret<S> {
+    pub fn params(&self) -> &RingPedersenParams<S> {
+        &self.params
+    }
+}
+
+impl<const H: usize, const H_UNSAT: usize, const S: usize, const S_UNSAT: usize, const D: usize, const Q: usize>
+    RingPedersenSecret<S>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    // the key has to be built from safe primes, then a random square generates (almost surely) the whole group of
+    // quadratic residues and s = t^lambda cannot be told apart from a random square
+    pub fn from_secret_key<R: CryptoRng + ?Sized>(sk: &SecretKey<H, S, D>, rng: &mut R) -> Self {
+        if !sk.p.as_ref().is_safe_prime_with_rng(rng) || !sk.q.as_ref().is_safe_prime_with_rng(rng) {
+            panic!("the key must be built from safe primes");
+        }
+
+        let n = sk.pk.n;
+        let phi = sk
+            .precomputation
+            .pm1
+            .widening_mul(&sk.precomputation.qm1)
+            .to_nz()
+            .expect("phi is non zero");
+        let monty_params = MontyParams::new(n);
+
+        let r = sk.pk.random_nonce(rng);
+        let t = MontyForm::new(&r, monty_params).square();
+        let lambda = Uint::random_mod(rng, &phi);
+        let s = t.pow(&lambda);
+
+        RingPedersenSecret {
+            params: RingPedersenParams {
+                n,
+                s: s.retrieve(),
+                t: t.retrieve(),
+                monty_params,
+            },
+            phi,
+            lambda,
+        }
+    }
+
+    pub fn random<R: CryptoRng + ?Sized>(rng: &mut R) -> Self {
+        let (sk, _) = SecretKey::<H, S, D>::builder().safe_primes(true).generate(rng);
+
+        Self::from_secret_key(&sk, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::KeyGenerator;
+    use crate::ring_pedersen::RingPedersenSecret;
+    use crate::sk::SecretKey;
+    use crypto_bigint::{U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type TestSecret = RingPedersenSecret<{ U512::LIMBS }>;
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+
+    #[test]
+    fn should_commit_and_verify() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let secret = TestSecret::random(&mut rng);
+        let params = secret.params();
+
+        let m = U512::from_u64(42);
+        let (commitment, r) = params.commit(&m, &mut rng);
+        assert!(bool::from(params.verify_commitment(&commitment, &m, &r)));
+        assert!(!bool::from(params.verify_commitment(
+            &commitment,
+            &U512::from_u64(43),
+            &r
+        )));
+    }
+
+    #[test]
+    #[should_panic(expected = "the key must be built from safe primes")]
+    fn should_reject_key_without_safe_primes() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, _) = SmallSecretKey::random(&mut rng);
+        TestSecret::from_secret_key(&sk, &mut rng);
+    }
+}
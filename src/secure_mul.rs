@@ -0,0 +1,208 @@
+use crate::pk::PublicKey;
+use crate::sk::SecretKey;
+use crate::traits::{DecryptionKey, EncryptionKey, HomomorphicKey, Key};
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, Split, Uint};
+use rand_core::CryptoRng;
+use std::fmt;
+
+// blinded multiplication: the evaluator sends Enc(x + a) and Enc(y + b) for uniform masks a, b, the decryptor returns
+// Enc((x + a)(y + b)) and the evaluator strips the masks, Enc(xy) = Enc((x + a)(y + b)) - b Enc(x) - a Enc(y) - ab;
+// the decryptor only sees uniformly distributed values, the evaluator only sees ciphertexts
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SecureMulError {
+    LengthMismatch,
+    InvalidCiphertext(usize),
+}
+
+impl fmt::Display for SecureMulError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecureMulError::LengthMismatch => write!(f, "number of products does not match the request"),
+            SecureMulError::InvalidCiphertext(i) => write!(f, "ciphertext {i} is not valid for the key"),
+        }
+    }
+}
+
+impl std::error::Error for SecureMulError {}
+
+#[derive(Debug, Clone)]
+pub struct MulRequest<const D: usize> {
+    pub blinded: Vec<(NonZero<Uint<D>>, NonZero<Uint<D>>)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MulResponse<const D: usize> {
+    pub products: Vec<NonZero<Uint<D>>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SecureMulEvaluator<const S: usize, const D: usize> {
+    key: PublicKey<S, D>,
+    factors: Vec<(NonZero<Uint<D>>, NonZero<Uint<D>>)>,
+    masks: Vec<(Uint<S>, Uint<S>)>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SecureMulDecryptor<'a, const H: usize, const S: usize, const D: usize> {
+    sk: &'a SecretKey<H, S, D>,
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize>
+    SecureMulEvaluator<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    // factors are pairs (Enc(x), Enc(y)) to be multiplied, all pairs travel in a single request
+    pub fn new<R: CryptoRng + ?Sized>(
+        key: PublicKey<S, D>,
+        factors: &[(NonZero<Uint<D>>, NonZero<Uint<D>>)],
+        rng: &mut R,
+    ) -> Result<(Self, MulRequest<D>), SecureMulError> {
+        if let Some(i) = factors
+            .iter()
+            .position(|(cx, cy)| !bool::from(key.ciphertext_is_valid(cx) & key.ciphertext_is_valid(cy)))
+        {
+            return Err(SecureMulError::InvalidCiphertext(i));
+        }
+
+        let masks: Vec<_> = factors
+            .iter()
+            .map(|_| (key.random_plaintext(rng), key.random_plaintext(rng)))
+            .collect();
+        let blinded = factors
+            .iter()
+            .zip(&masks)
+            .map(|((cx, cy), (a, b))| (key.ciphertext_add_plain(cx, a), key.ciphertext_add_plain(cy, b)))
+            .collect();
+
+        let evaluator = SecureMulEvaluator {
+            key,
+            factors: factors.to_vec(),
+            masks,
+        };
+        Ok((evaluator, MulRequest { blinded }))
+    }
+
+    pub fn finish(self, response: &MulResponse<D>) -> Result<Vec<NonZero<Uint<D>>>, SecureMulError> {
+        if response.products.len() != self.factors.len() {
+            return Err(SecureMulError::LengthMismatch);
+        }
+        if let Some(i) = response
+            .products
+            .iter()
+            .position(|c| !bool::from(self.key.ciphertext_is_valid(c)))
+        {
+            return Err(SecureMulError::InvalidCiphertext(i));
+        }
+
+        let key = &self.key;
+        let products = response
+            .products
+            .iter()
+            .zip(&self.factors)
+            .zip(&self.masks)
+            .map(|((c, (cx, cy)), (a, b))| {
+                let c = key.ciphertext_sub(c, &key.ciphertext_mul_scalar(cx, b));
+                let c = key.ciphertext_sub(&c, &key.ciphertext_mul_scalar(cy, a));
+                key.ciphertext_sub_plain(&c, &a.mul_mod(b, key.n.as_nz_ref()))
+            })
+            .collect();
+
+        Ok(products)
+    }
+}
+
+impl<
+    'a,
+    const H: usize,
+    const H_UNSAT: usize,
+    const S: usize,
+    const S_UNSAT: usize,
+    const D: usize,
+    const D_UNSAT: usize,
+    const Q: usize,
+> SecureMulDecryptor<'a, H, S, D>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn new(sk: &'a SecretKey<H, S, D>) -> Self {
+        SecureMulDecryptor { sk }
+    }
+
+    pub fn respond<R: CryptoRng + ?Sized>(
+        &self,
+        request: &MulRequest<D>,
+        rng: &mut R,
+    ) -> Result<MulResponse<D>, SecureMulError> {
+        let n = self.sk.pk.n.as_nz_ref();
+        let products = request
+            .blinded
+            .iter()
+            .enumerate()
+            .map(|(i, (cx, cy))| {
+                let x = Option::<Uint<S>>::from(self.sk.try_decrypt(cx)).ok_or(SecureMulError::InvalidCiphertext(i))?;
+                let y = Option::<Uint<S>>::from(self.sk.try_decrypt(cy)).ok_or(SecureMulError::InvalidCiphertext(i))?;
+                Ok(self.sk.pk.encrypt(&x.mul_mod(&y, n), rng).0)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(MulResponse { products })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::secure_mul::{MulResponse, SecureMulDecryptor, SecureMulError, SecureMulEvaluator};
+    use crate::sk::SecretKey;
+    use crate::{DecryptionKey, EncryptionKey, KeyGenerator};
+    use crypto_bigint::{U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+
+    #[test]
+    fn should_multiply_encrypted_values_in_batch() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+
+        let values: Vec<_> = (0..4)
+            .map(|_| (pk.random_plaintext(&mut rng), pk.random_plaintext(&mut rng)))
+            .collect();
+        let factors: Vec<_> = values
+            .iter()
+            .map(|(x, y)| (pk.encrypt(x, &mut rng).0, pk.encrypt(y, &mut rng).0))
+            .collect();
+
+        let (evaluator, request) = SecureMulEvaluator::new(pk, &factors, &mut rng).unwrap();
+        let response = SecureMulDecryptor::new(&sk).respond(&request, &mut rng).unwrap();
+        let products = evaluator.finish(&response).unwrap();
+
+        for ((x, y), c) in values.iter().zip(&products) {
+            assert_eq!(sk.decrypt(c), x.mul_mod(y, pk.n.as_nz_ref()));
+        }
+    }
+
+    #[test]
+    fn should_reject_mismatched_response() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (_, pk) = SmallSecretKey::random(&mut rng);
+        let factors = [(pk.encrypt(&U512::ONE, &mut rng).0, pk.encrypt(&U512::ONE, &mut rng).0)];
+
+        let (evaluator, _) = SecureMulEvaluator::new(pk, &factors, &mut rng).unwrap();
+        let response = MulResponse { products: Vec::new() };
+        assert_eq!(evaluator.finish(&response).unwrap_err(), SecureMulError::LengthMismatch);
+    }
+}
@@ -1,9 +1,18 @@
+mod builder;
 mod decrypt;
+#[cfg(feature = "json")]
+mod json;
 mod keygen;
+#[cfg(feature = "unstable-pkcs8")]
+mod pkcs8;
 mod precomp;
 
+pub use crate::sk::builder::{KeyProperties, SecretKeyBuilder};
+
 use crate::pk::PublicKey;
 use crate::sk::precomp::SecretPrecomputation;
+#[cfg(any(feature = "json", feature = "unstable-pkcs8"))]
+use crate::utils::is_probable_prime;
 use crate::utils::odd_widening_mul;
 use crypto_bigint::modular::SafeGcdInverter;
 use crypto_bigint::{Concat, Odd, PrecomputeInverter, Split, Uint};
@@ -54,6 +63,20 @@ where
         }
     }
 
+    // decoded primes are untrusted, anything passing this check is safe to feed to from_primes_unchecked
+    #[cfg(any(feature = "json", feature = "unstable-pkcs8"))]
+    pub(crate) fn primes_are_valid(p: &Odd<Uint<H>>, q: &Odd<Uint<H>>) -> bool {
+        let n = odd_widening_mul(p, q);
+        let phi = p.wrapping_sub(&Uint::ONE).widening_mul(&q.wrapping_sub(&Uint::ONE));
+
+        p != q
+            && p.bits() == Uint::<H>::BITS
+            && q.bits() == Uint::<H>::BITS
+            && is_probable_prime(p)
+            && is_probable_prime(q)
+            && n.gcd(&phi) == Uint::ONE
+    }
+
     pub fn as_public_key(&self) -> PublicKey<S, D> {
         self.pk.to_owned()
     }
@@ -0,0 +1,194 @@
+use crate::sk::SecretKey;
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, Odd, PrecomputeInverter, Split, Uint};
+use crypto_primes::RandomPrimeWithRng;
+use rand_core::CryptoRng;
+
+#[derive(Debug, Copy, Clone)]
+pub struct SecretKeyBuilder<const H: usize, const S: usize, const D: usize> {
+    safe_primes: bool,
+    blum_primes: bool,
+    min_prime_distance_bits: u32,
+    coprime_n_phi: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct KeyProperties {
+    pub safe_primes: bool,
+    pub blum_primes: bool,
+    pub prime_distance_bits: u32,
+    pub coprime_n_phi: bool,
+}
+
+impl<const H: usize, const H_UNSAT: usize, const S: usize, const S_UNSAT: usize, const D: usize, const Q: usize> Default
+    for SecretKeyBuilder<H, S, D>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const H: usize, const H_UNSAT: usize, const S: usize, const S_UNSAT: usize, const D: usize, const Q: usize>
+    SecretKeyBuilder<H, S, D>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn new() -> Self {
+        SecretKeyBuilder {
+            safe_primes: false,
+            blum_primes: false,
+            min_prime_distance_bits: 0,
+            coprime_n_phi: false,
+        }
+    }
+
+    pub fn safe_primes(mut self, enabled: bool) -> Self {
+        self.safe_primes = enabled;
+        self
+    }
+
+    pub fn blum_primes(mut self, enabled: bool) -> Self {
+        self.blum_primes = enabled;
+        self
+    }
+
+    pub fn min_prime_distance_bits(mut self, bits: u32) -> Self {
+        assert!(bits <= Uint::<H>::BITS, "|p - q| cannot be longer than the primes");
+        self.min_prime_distance_bits = bits;
+        self
+    }
+
+    pub fn coprime_n_phi(mut self, enabled: bool) -> Self {
+        self.coprime_n_phi = enabled;
+        self
+    }
+
+    pub fn generate<R: CryptoRng + ?Sized>(&self, rng: &mut R) -> (SecretKey<H, S, D>, KeyProperties) {
+        let p = self.generate_prime(rng);
+        let sk = loop {
+            let q = self.generate_prime(rng);
+            if p == q || prime_distance_bits(&p, &q) < self.min_prime_distance_bits {
+                continue;
+            }
+
+            let sk = SecretKey::from_primes_unchecked(p, q);
+            if !self.coprime_n_phi || sk.is_n_phi_coprime() {
+                break sk;
+            }
+        };
+
+        let properties = sk.properties(rng);
+        (sk, properties)
+    }
+
+    fn generate_prime<R: CryptoRng + ?Sized>(&self, rng: &mut R) -> Odd<Uint<H>> {
+        loop {
+            let p = if self.safe_primes {
+                Uint::generate_safe_prime_with_rng(rng, Uint::<H>::BITS)
+            } else {
+                Uint::generate_prime_with_rng(rng, Uint::<H>::BITS)
+            };
+
+            if !self.blum_primes || is_blum_prime(&p) {
+                return p.to_odd().expect("p is an odd prime");
+            }
+        }
+    }
+}
+
+impl<const H: usize, const H_UNSAT: usize, const S: usize, const S_UNSAT: usize, const D: usize, const Q: usize>
+    SecretKey<H, S, D>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn builder() -> SecretKeyBuilder<H, S, D> {
+        SecretKeyBuilder::new()
+    }
+
+    pub fn properties<R: CryptoRng + ?Sized>(&self, rng: &mut R) -> KeyProperties {
+        KeyProperties {
+            safe_primes: self.p.as_ref().is_safe_prime_with_rng(rng) && self.q.as_ref().is_safe_prime_with_rng(rng),
+            blum_primes: is_blum_prime(&self.p) && is_blum_prime(&self.q),
+            prime_distance_bits: prime_distance_bits(&self.p, &self.q),
+            coprime_n_phi: self.is_n_phi_coprime(),
+        }
+    }
+
+    fn is_n_phi_coprime(&self) -> bool {
+        let pm1 = self.p.wrapping_sub(&Uint::ONE);
+        let qm1 = self.q.wrapping_sub(&Uint::ONE);
+        let phi = pm1.widening_mul(&qm1);
+
+        self.pk.n.gcd(&phi) == Uint::ONE
+    }
+}
+
+fn is_blum_prime<const H: usize>(p: &Uint<H>) -> bool {
+    // p = 3 (mod 4)
+    p.as_limbs()[0].0 & 3 == 3
+}
+
+fn prime_distance_bits<const H: usize>(p: &Uint<H>, q: &Uint<H>) -> u32 {
+    if p > q {
+        p.wrapping_sub(q).bits()
+    } else {
+        q.wrapping_sub(p).bits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sk::SecretKey;
+    use crate::{EncryptionKey, OpeningKey, PaillierSecretKey2048};
+    use crypto_bigint::{U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+
+    #[test]
+    fn should_generate_safe_prime_key() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, properties) = SmallSecretKey::builder()
+            .safe_primes(true)
+            .min_prime_distance_bits(200)
+            .coprime_n_phi(true)
+            .generate(&mut rng);
+
+        assert!(properties.safe_primes);
+        assert!(properties.blum_primes);
+        assert!(properties.prime_distance_bits >= 200);
+        assert!(properties.coprime_n_phi);
+
+        let pk = sk.as_public_key();
+        let m = pk.random_plaintext(&mut rng);
+        let (c, r) = pk.encrypt(&m, &mut rng);
+        assert_eq!(sk.open(&c), (m, r));
+    }
+
+    #[test]
+    fn should_generate_blum_prime_key() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, properties) = PaillierSecretKey2048::builder().blum_primes(true).generate(&mut rng);
+
+        assert!(properties.blum_primes);
+        assert_eq!(properties, sk.properties(&mut rng));
+    }
+}
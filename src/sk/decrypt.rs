@@ -1,9 +1,9 @@
 use crate::sk::SecretKey;
 use crate::traits::{DecryptionKey, Key, OpeningKey};
-use crate::utils::{wide_div, wide_rem, wider_rem};
+use crate::utils::{fermat_quotient, wider_rem};
 use crypto_bigint::modular::{MontyForm, SafeGcdInverter};
 use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, Split, Uint};
-use subtle::Choice;
+use subtle::{Choice, ConditionallySelectable, CtOption};
 
 impl<const H: usize, const H_UNSAT: usize, const S: usize, const D: usize, const Q: usize> SecretKey<H, S, D>
 where
@@ -14,21 +14,21 @@ where
     Uint<Q>: Split<Output = Uint<D>>,
 {
     fn fermat_quotient_p(&self, x: &Uint<D>) -> Uint<H> {
-        let x_reduced = wide_rem(x, self.precomputation.pp_monty_params.modulus().as_nz_ref());
-        let x_monty_form = MontyForm::new(&x_reduced, self.precomputation.pp_monty_params);
-        let x_to_pm1 = x_monty_form.pow(&self.precomputation.pm1).retrieve();
-        let nom = x_to_pm1 - Uint::ONE;
-
-        wide_div(&nom, self.p.as_nz_ref())
+        fermat_quotient(
+            x,
+            &self.p,
+            &self.precomputation.pp_monty_params,
+            &self.precomputation.pm1,
+        )
     }
 
     fn fermat_quotient_q(&self, x: &Uint<D>) -> Uint<H> {
-        let x_reduced = wide_rem(x, self.precomputation.qq_monty_params.modulus().as_nz_ref());
-        let x_monty_form = MontyForm::new(&x_reduced, self.precomputation.qq_monty_params);
-        let x_to_qm1 = x_monty_form.pow(&self.precomputation.qm1);
-        let nom = x_to_qm1.retrieve() - Uint::ONE;
-
-        wide_div(&nom, self.q.as_nz_ref())
+        fermat_quotient(
+            x,
+            &self.q,
+            &self.precomputation.qq_monty_params,
+            &self.precomputation.qm1,
+        )
     }
 
     fn crt(&self, mp: &Uint<H>, mq: &Uint<H>) -> Uint<S> {
@@ -139,14 +139,22 @@ where
 
         (p, r)
     }
+
+    fn try_open(&self, c: &Self::Ciphertext) -> CtOption<(Uint<S>, Self::Nonce)> {
+        // the nonce of a multiple of n is zero, so invalid ciphertexts are swapped for a trivial one
+        let is_valid = self.ciphertext_is_valid(c);
+        let c_checked = NonZero::conditional_select(&NonZero::ONE, c, is_valid);
+
+        CtOption::new(self.open(&c_checked), is_valid)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::EncryptionKey;
-    use crate::PaillierSecretKey4096;
-    use crate::traits::OpeningKey;
-    use crypto_bigint::{U2048, U4096};
+    use crate::traits::{DecryptionKey, KeyGenerator, OpeningKey};
+    use crate::{PaillierSecretKey2048, PaillierSecretKey4096};
+    use crypto_bigint::{NonZero, U2048, U4096};
     use rand_chacha::ChaCha8Rng;
     use rand_chacha::rand_core::SeedableRng;
 
@@ -170,4 +178,32 @@ mod tests {
         assert_eq!(m, m2);
         assert_eq!(r, r2);
     }
+
+    #[test]
+    fn should_try_decrypt_valid_ciphertext() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = PaillierSecretKey2048::random(&mut rng);
+
+        let m = pk.random_plaintext(&mut rng);
+        let (c, r) = pk.encrypt(&m, &mut rng);
+
+        assert_eq!(sk.try_decrypt(&c).unwrap(), m);
+        assert_eq!(sk.try_open(&c).unwrap(), (m, r));
+    }
+
+    #[test]
+    fn should_reject_invalid_ciphertext() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = PaillierSecretKey2048::random(&mut rng);
+
+        let m = pk.random_plaintext(&mut rng);
+        let (c, _) = pk.encrypt(&m, &mut rng);
+        let c_too_big = NonZero::new(c.wrapping_add(pk.precomputation.nn_monty_params.modulus())).unwrap();
+        let c_not_coprime = NonZero::new(sk.p.resize().wrapping_mul(&U2048::from(7u32))).unwrap();
+
+        assert!(bool::from(sk.try_decrypt(&c_too_big).is_none()));
+        assert!(bool::from(sk.try_decrypt(&c_not_coprime).is_none()));
+        assert!(bool::from(sk.try_open(&c_too_big).is_none()));
+        assert!(bool::from(sk.try_open(&c_not_coprime).is_none()));
+    }
 }
@@ -0,0 +1,107 @@
+use crate::pk::PublicKey;
+use crate::pk::{JsonError, KEY_TYPE, PublicJwk, decode_integer, encode_integer};
+use crate::sk::SecretKey;
+use crate::utils::odd_widening_mul;
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, Odd, PrecomputeInverter, Split, Uint};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct SecretJwk {
+    kty: String,
+    #[serde(default)]
+    key_ops: Vec<String>,
+    p: String,
+    q: String,
+    #[serde(rename = "pub")]
+    public_key: PublicJwk,
+}
+
+impl<const H: usize, const H_UNSAT: usize, const S: usize, const S_UNSAT: usize, const D: usize, const Q: usize>
+    SecretKey<H, S, D>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn to_jwk(&self) -> String {
+        let jwk = SecretJwk {
+            kty: KEY_TYPE.into(),
+            key_ops: vec!["decrypt".into()],
+            p: encode_integer(self.p.as_ref()),
+            q: encode_integer(self.q.as_ref()),
+            public_key: self.pk.public_jwk(),
+        };
+
+        serde_json::to_string(&jwk).expect("JWK is serializable")
+    }
+
+    pub fn from_jwk(json: &str) -> Result<Self, JsonError> {
+        let jwk: SecretJwk = serde_json::from_str(json)?;
+        if jwk.kty != KEY_TYPE {
+            return Err(JsonError::UnsupportedKeyType);
+        }
+
+        let pk = PublicKey::<S, D>::from_public_jwk(&jwk.public_key)?;
+        let p: Odd<Uint<H>> = Option::from(decode_integer::<H>(&jwk.p)?.to_odd()).ok_or(JsonError::InvalidKey)?;
+        let q: Odd<Uint<H>> = Option::from(decode_integer::<H>(&jwk.q)?.to_odd()).ok_or(JsonError::InvalidKey)?;
+        if !SecretKey::primes_are_valid(&p, &q) || odd_widening_mul(&p, &q) != pk.n {
+            return Err(JsonError::InvalidKey);
+        }
+
+        Ok(SecretKey::from_primes_unchecked(p, q))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pk::{JsonError, encode_integer};
+    use crate::{EncryptionKey, KeyGenerator, OpeningKey, PaillierSecretKey2048};
+    use crypto_bigint::U64;
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn should_encode_and_decode_secret_key() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = PaillierSecretKey2048::random(&mut rng);
+
+        let decoded = PaillierSecretKey2048::from_jwk(&sk.to_jwk()).unwrap();
+        let m = pk.random_plaintext(&mut rng);
+        let (c, r) = pk.encrypt(&m, &mut rng);
+        assert_eq!(decoded.open(&c), (m, r));
+    }
+
+    #[test]
+    fn should_reject_mismatched_public_key() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, _) = PaillierSecretKey2048::random(&mut rng);
+        let (_, other_pk) = PaillierSecretKey2048::random(&mut rng);
+
+        let mut jwk: serde_json::Value = serde_json::from_str(&sk.to_jwk()).unwrap();
+        jwk["pub"] = serde_json::from_str(&other_pk.to_jwk()).unwrap();
+        assert!(matches!(
+            PaillierSecretKey2048::from_jwk(&jwk.to_string()),
+            Err(JsonError::InvalidKey)
+        ));
+    }
+
+    #[test]
+    fn should_reject_short_primes_with_matching_modulus() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, _) = PaillierSecretKey2048::random(&mut rng);
+
+        // 3 * 7 = 21 passes the product check, but the primes are far below the 1024 bits a prime of this key must have
+        let mut jwk: serde_json::Value = serde_json::from_str(&sk.to_jwk()).unwrap();
+        jwk["p"] = encode_integer(&U64::from_u8(3)).into();
+        jwk["q"] = encode_integer(&U64::from_u8(7)).into();
+        jwk["pub"]["n"] = encode_integer(&U64::from_u8(21)).into();
+        assert!(matches!(
+            PaillierSecretKey2048::from_jwk(&jwk.to_string()),
+            Err(JsonError::InvalidKey)
+        ));
+    }
+}
@@ -1,5 +1,5 @@
 use rand_core::CryptoRng;
-use subtle::Choice;
+use subtle::{Choice, CtOption};
 
 pub trait Key<P> {
     type Ciphertext;
@@ -41,10 +41,22 @@ pub trait EncryptionKey<P>: Key<P> {
 
 pub trait DecryptionKey<P>: Key<P> {
     fn decrypt(&self, ciphertext: &Self::Ciphertext) -> P;
+
+    // decrypts unconditionally so that the running time does not depend on validity, implementations whose
+    // decrypt may panic on an invalid ciphertext override this to decrypt a valid substitute instead
+    fn try_decrypt(&self, ciphertext: &Self::Ciphertext) -> CtOption<P> {
+        let is_valid = self.ciphertext_is_valid(ciphertext);
+        CtOption::new(self.decrypt(ciphertext), is_valid)
+    }
 }
 
 pub trait OpeningKey<P>: DecryptionKey<P> {
     fn open(&self, ciphertext: &Self::Ciphertext) -> (P, Self::Nonce);
+
+    fn try_open(&self, ciphertext: &Self::Ciphertext) -> CtOption<(P, Self::Nonce)> {
+        let is_valid = self.ciphertext_is_valid(ciphertext);
+        CtOption::new(self.open(ciphertext), is_valid)
+    }
 }
 
 pub trait KeyGenerator<P>: DecryptionKey<P> + Sized {
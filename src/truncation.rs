@@ -0,0 +1,268 @@
+use crate::comparison::{
+    BlindedTests, ComparisonDecryptor, ComparisonError, ComparisonEvaluator, MaskedBits, MaskedDifference,
+    ZeroTestResult,
+};
+use crate::pk::PublicKey;
+use crate::sk::SecretKey;
+use crate::traits::{DecryptionKey, EncryptionKey, HomomorphicKey, Key};
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, RandomBits, Split, Uint};
+use rand_core::CryptoRng;
+use std::fmt;
+
+const STATISTICAL_SECURITY_BITS: u32 = 128;
+
+// truncation by a public divisor d: the evaluator sends Enc(x + r) for a statistical mask r, the decryptor returns
+// Enc((x + r) div d) and Enc((x + r) mod d), then x div d = (x + r) div d - r div d - [(x + r) mod d < r mod d];
+// the one round result skips the last term and is either x div d or x div d + 1, the exact result obtains the
+// borrow with one more comparison round
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TruncationError {
+    BoundTooLarge,
+    InvalidCiphertext,
+    Comparison(ComparisonError),
+}
+
+impl fmt::Display for TruncationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TruncationError::BoundTooLarge => write!(f, "bit length bound does not fit the key"),
+            TruncationError::InvalidCiphertext => write!(f, "ciphertext is not valid for the key"),
+            TruncationError::Comparison(e) => write!(f, "borrow comparison failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TruncationError {}
+
+impl From<ComparisonError> for TruncationError {
+    fn from(e: ComparisonError) -> Self {
+        TruncationError::Comparison(e)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TruncationRequest<const S: usize, const D: usize> {
+    pub c: NonZero<Uint<D>>,
+    pub divisor: NonZero<Uint<S>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TruncationResponse<const D: usize> {
+    pub quotient: NonZero<Uint<D>>,
+    pub remainder: NonZero<Uint<D>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TruncationEvaluator<const S: usize, const D: usize> {
+    key: PublicKey<S, D>,
+    divisor: NonZero<Uint<S>>,
+    mask: Uint<S>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExactTruncation<const S: usize, const D: usize> {
+    key: PublicKey<S, D>,
+    approximation: NonZero<Uint<D>>,
+    borrow: ComparisonEvaluator<S, D>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TruncationDecryptor<'a, const H: usize, const S: usize, const D: usize> {
+    sk: &'a SecretKey<H, S, D>,
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize>
+    TruncationEvaluator<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    // x is an encryption of an integer below 2^bits
+    pub fn new<R: CryptoRng + ?Sized>(
+        key: PublicKey<S, D>,
+        x: &NonZero<Uint<D>>,
+        bits: u32,
+        divisor: NonZero<Uint<S>>,
+        rng: &mut R,
+    ) -> Result<(Self, TruncationRequest<S, D>), TruncationError> {
+        // x + r < 2^(bits + kappa + 1) must not wrap around n
+        if key.n.bits() <= bits + STATISTICAL_SECURITY_BITS + 1 {
+            return Err(TruncationError::BoundTooLarge);
+        }
+        if !bool::from(key.ciphertext_is_valid(x)) {
+            return Err(TruncationError::InvalidCiphertext);
+        }
+
+        let mask = Uint::<S>::random_bits(rng, bits + STATISTICAL_SECURITY_BITS);
+        let masked = key.ciphertext_add_plain(x, &mask);
+        let c = key.ciphertext_add(&masked, &key.encrypt(&Uint::ZERO, rng).0);
+
+        let evaluator = TruncationEvaluator { key, divisor, mask };
+        Ok((evaluator, TruncationRequest { c, divisor }))
+    }
+
+    // either Enc(x div d) or Enc(x div d + 1)
+    pub fn finish(&self, response: &TruncationResponse<D>) -> Result<NonZero<Uint<D>>, TruncationError> {
+        if !bool::from(self.key.ciphertext_is_valid(&response.quotient)) {
+            return Err(TruncationError::InvalidCiphertext);
+        }
+
+        let (mask_quotient, _) = self.mask.div_rem(&self.divisor);
+        Ok(self.key.ciphertext_sub_plain(&response.quotient, &mask_quotient))
+    }
+
+    // starts the comparison of (x + r) mod d with r mod d, the decryptor answers with a `ComparisonDecryptor`
+    // bounded by the bit length of d
+    pub fn finish_exact<R: CryptoRng + ?Sized>(
+        &self,
+        response: &TruncationResponse<D>,
+        rng: &mut R,
+    ) -> Result<(ExactTruncation<S, D>, MaskedDifference<D>), TruncationError> {
+        let approximation = self.finish(response)?;
+
+        let (_, mask_remainder) = self.mask.div_rem(&self.divisor);
+        let encrypted_remainder = self.key.encrypt_with_nonce(&mask_remainder, &NonZero::ONE);
+        let (borrow, message) = ComparisonEvaluator::new(
+            self.key,
+            &response.remainder,
+            &encrypted_remainder,
+            self.divisor.bits(),
+            rng,
+        )?;
+
+        let exact = ExactTruncation {
+            key: self.key,
+            approximation,
+            borrow,
+        };
+        Ok((exact, message))
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> ExactTruncation<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn blind<R: CryptoRng + ?Sized>(
+        &mut self,
+        message: &MaskedBits<D>,
+        rng: &mut R,
+    ) -> Result<BlindedTests<D>, TruncationError> {
+        Ok(self.borrow.blind(message, rng)?)
+    }
+
+    pub fn finish(self, message: &ZeroTestResult<D>) -> Result<NonZero<Uint<D>>, TruncationError> {
+        let borrow = self.borrow.finish(message)?;
+        Ok(self.key.ciphertext_sub(&self.approximation, &borrow))
+    }
+}
+
+impl<
+    'a,
+    const H: usize,
+    const H_UNSAT: usize,
+    const S: usize,
+    const S_UNSAT: usize,
+    const D: usize,
+    const D_UNSAT: usize,
+    const Q: usize,
+> TruncationDecryptor<'a, H, S, D>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn new(sk: &'a SecretKey<H, S, D>) -> Self {
+        TruncationDecryptor { sk }
+    }
+
+    pub fn respond<R: CryptoRng + ?Sized>(
+        &self,
+        request: &TruncationRequest<S, D>,
+        rng: &mut R,
+    ) -> Result<TruncationResponse<D>, TruncationError> {
+        let masked =
+            Option::<Uint<S>>::from(self.sk.try_decrypt(&request.c)).ok_or(TruncationError::InvalidCiphertext)?;
+        let (quotient, remainder) = masked.div_rem(&request.divisor);
+
+        let pk = &self.sk.pk;
+        Ok(TruncationResponse {
+            quotient: pk.encrypt(&quotient, rng).0,
+            remainder: pk.encrypt(&remainder, rng).0,
+        })
+    }
+
+    // decryptor side of the exact mode
+    pub fn borrow_decryptor(
+        &self,
+        request: &TruncationRequest<S, D>,
+    ) -> Result<ComparisonDecryptor<'a, H, S, D>, TruncationError> {
+        Ok(ComparisonDecryptor::new(self.sk, request.divisor.bits())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sk::SecretKey;
+    use crate::truncation::{TruncationDecryptor, TruncationEvaluator};
+    use crate::{DecryptionKey, EncryptionKey, KeyGenerator};
+    use crypto_bigint::{NonZero, U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+
+    #[test]
+    fn should_truncate_within_one() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let decryptor = TruncationDecryptor::new(&sk);
+        let divisor = NonZero::new(U512::from_u32(1 << 16)).unwrap();
+
+        for x in [0u64, 1, 65535, 65536, 123_456_789, u32::MAX as u64] {
+            let c = pk.encrypt(&U512::from_u64(x), &mut rng).0;
+            let (evaluator, request) = TruncationEvaluator::new(pk, &c, 32, divisor, &mut rng).unwrap();
+            let response = decryptor.respond(&request, &mut rng).unwrap();
+            let result = sk.decrypt(&evaluator.finish(&response).unwrap());
+
+            let expected = x >> 16;
+            assert!(result == U512::from_u64(expected) || result == U512::from_u64(expected + 1));
+        }
+    }
+
+    #[test]
+    fn should_truncate_exactly() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let decryptor = TruncationDecryptor::new(&sk);
+        let divisor = NonZero::new(U512::from_u32(1000)).unwrap();
+
+        for x in [0u64, 999, 1000, 1001, 4_294_967_295] {
+            let c = pk.encrypt(&U512::from_u64(x), &mut rng).0;
+            let (evaluator, request) = TruncationEvaluator::new(pk, &c, 32, divisor, &mut rng).unwrap();
+            let response = decryptor.respond(&request, &mut rng).unwrap();
+
+            let borrow_decryptor = decryptor.borrow_decryptor(&request).unwrap();
+            let (mut exact, masked) = evaluator.finish_exact(&response, &mut rng).unwrap();
+            let bits = borrow_decryptor.decompose(&masked, &mut rng).unwrap();
+            let tests = exact.blind(&bits, &mut rng).unwrap();
+            let result = borrow_decryptor.test_zeros(&tests, &mut rng).unwrap();
+            let c = exact.finish(&result).unwrap();
+
+            assert_eq!(sk.decrypt(&c), U512::from_u64(x / 1000));
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use crypto_bigint::modular::{MontyForm, MontyParams};
 use crypto_bigint::{Concat, NonZero, Odd, Split, Uint};
+#[cfg(any(feature = "json", feature = "unstable-pkcs8"))]
+use crypto_primes::hazmat::{AStarBase, LucasCheck, MillerRabin, lucas_test};
+use rand_core::CryptoRng;
 
 pub(crate) fn odd_widening_mul<const L: usize, const LL: usize>(x: &Odd<Uint<L>>, y: &Odd<Uint<L>>) -> Odd<Uint<LL>>
 where
@@ -71,3 +74,77 @@ where
 {
     n.div_rem(&d.resize().to_nz().unwrap()).0.resize()
 }
+
+pub(crate) fn fermat_quotient<const H: usize, const S: usize, const D: usize>(
+    x: &Uint<D>,
+    p: &Odd<Uint<H>>,
+    pp_monty_params: &MontyParams<S>,
+    pm1: &Uint<H>,
+) -> Uint<H>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Uint<D>: Split<Output = Uint<S>>,
+{
+    // L(x) = (x^(p - 1) mod p^2 - 1) / p
+    let x_reduced = wide_rem(x, pp_monty_params.modulus().as_nz_ref());
+    let x_monty_form = MontyForm::new(&x_reduced, *pp_monty_params);
+    let x_to_pm1 = x_monty_form.pow(pm1).retrieve();
+    // x^(p - 1) is 1 modulo p for units, wrapping keeps decryption of non units panic free
+    let nom = x_to_pm1.wrapping_sub(&Uint::ONE);
+
+    wide_div(&nom, p.as_nz_ref())
+}
+
+#[cfg(any(feature = "json", feature = "unstable-pkcs8"))]
+pub(crate) fn is_probable_prime<const L: usize>(x: &Odd<Uint<L>>) -> bool {
+    // Baillie-PSW, the deterministic part of is_prime_with_rng, for decoders that have no rng at hand
+    MillerRabin::new(*x).test_base_two().is_probably_prime()
+        && lucas_test(*x, AStarBase, LucasCheck::Strong).is_probably_prime()
+}
+
+pub(crate) fn small_primes(bound: u32) -> Vec<u32> {
+    // sieve of Eratosthenes over [2, bound]
+    let bound = bound as usize;
+    let mut is_composite = vec![false; bound + 1];
+    let mut primes = Vec::new();
+    for i in 2..=bound {
+        if !is_composite[i] {
+            primes.push(i as u32);
+            for j in (i * i..=bound).step_by(i) {
+                is_composite[j] = true;
+            }
+        }
+    }
+
+    primes
+}
+
+pub(crate) fn shuffle<T, R: CryptoRng + ?Sized>(items: &mut [T], rng: &mut R) {
+    // Fisher-Yates, the modulo bias is negligible for short slices
+    for i in (1..items.len()).rev() {
+        let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+pub(crate) fn uint_to_be_bytes<const L: usize>(x: &Uint<L>) -> Vec<u8> {
+    x.as_limbs()
+        .iter()
+        .rev()
+        .flat_map(|limb| limb.0.to_be_bytes())
+        .collect()
+}
+
+pub(crate) fn uint_from_be_bytes<const L: usize>(bytes: &[u8]) -> Option<Uint<L>> {
+    // shorter inputs are zero padded, longer ones are accepted only if the excess bytes are zero
+    let leading_zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    let bytes = &bytes[leading_zeros..];
+    if bytes.len() > Uint::<L>::BYTES {
+        return None;
+    }
+
+    let mut padded = vec![0u8; Uint::<L>::BYTES];
+    padded[Uint::<L>::BYTES - bytes.len()..].copy_from_slice(bytes);
+    Some(Uint::from_be_slice(&padded))
+}
@@ -0,0 +1,499 @@
+use crate::pk::PublicKey;
+use crate::sk::SecretKey;
+use crate::traits::{EncryptionKey, HomomorphicKey, Key};
+use crate::utils::uint_to_be_bytes;
+use crate::zk::{DecryptionProof, DecryptionProofMode, MembershipProof};
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{CheckedMul, Concat, NonZero, Odd, PrecomputeInverter, Split, Uint};
+use rand_core::CryptoRng;
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BallotEncoding {
+    // one ciphertext per candidate, each encrypting 0 or 1 and summing up to 1
+    OneHot,
+    // a single ciphertext encrypting base^choice, the base has to exceed the number of voters
+    Packed { base: u32 },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VotingError {
+    NoCandidates,
+    InvalidBase,
+    ModulusTooShort,
+    InvalidChoice,
+    InvalidBallot(usize),
+    DuplicateBallot(usize),
+    TooManyVoters,
+    MalformedTally,
+}
+
+impl fmt::Display for VotingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VotingError::NoCandidates => write!(f, "election has no candidates"),
+            VotingError::InvalidBase => write!(f, "packing base has to be at least 2"),
+            VotingError::ModulusTooShort => write!(f, "paillier modulus is too short for the packed ballots"),
+            VotingError::InvalidChoice => write!(f, "choice is not a candidate"),
+            VotingError::InvalidBallot(i) => write!(f, "ballot {i} is not valid"),
+            VotingError::DuplicateBallot(i) => write!(f, "ballot {i} repeats an earlier voter or ciphertext"),
+            VotingError::TooManyVoters => write!(f, "number of voters does not fit into the packing base"),
+            VotingError::MalformedTally => write!(f, "tally does not match the election"),
+        }
+    }
+}
+
+impl std::error::Error for VotingError {}
+
+#[derive(Debug, Clone)]
+pub struct Election<const S: usize, const D: usize> {
+    key: PublicKey<S, D>,
+    candidates: usize,
+    encoding: BallotEncoding,
+    // plaintexts a single ballot entry may encrypt
+    entry_set: Vec<Uint<S>>,
+    id: Vec<u8>,
+}
+
+// the proofs are bound to the election id and the voter, so a ballot cannot be replayed in another election or
+// under another name
+#[derive(Debug, Clone)]
+pub struct Ballot<const S: usize, const D: usize> {
+    pub voter: Vec<u8>,
+    pub ciphertexts: Vec<NonZero<Uint<D>>>,
+    pub proofs: Vec<MembershipProof<S, D>>,
+    // one hot ballots only, proves that the entries add up to 1
+    pub sum_proof: Option<MembershipProof<S, D>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Tally<const D: usize> {
+    pub ciphertexts: Vec<NonZero<Uint<D>>>,
+    pub voters: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct TallyDecryption<const S: usize, const D: usize> {
+    pub plaintexts: Vec<Uint<S>>,
+    pub proofs: Vec<DecryptionProof<S, D>>,
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> Election<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn new(
+        key: PublicKey<S, D>,
+        candidates: usize,
+        encoding: BallotEncoding,
+        id: &[u8],
+    ) -> Result<Self, VotingError> {
+        if candidates == 0 {
+            return Err(VotingError::NoCandidates);
+        }
+
+        let entry_set = match encoding {
+            BallotEncoding::OneHot => vec![Uint::ZERO, Uint::ONE],
+            BallotEncoding::Packed { base } => {
+                if base < 2 {
+                    return Err(VotingError::InvalidBase);
+                }
+
+                // base^candidates bounds the packed tally, it has to stay below n
+                let base = Uint::<S>::from_u32(base);
+                let mut powers = vec![Uint::ONE];
+                let mut power = Uint::<S>::ONE;
+                for i in 0..candidates {
+                    power = Option::from(power.checked_mul(&base)).ok_or(VotingError::ModulusTooShort)?;
+                    if i + 1 < candidates {
+                        powers.push(power);
+                    }
+                }
+                if !bool::from(key.plaintext_is_valid(&power)) {
+                    return Err(VotingError::ModulusTooShort);
+                }
+
+                powers
+            }
+        };
+
+        Ok(Election {
+            key,
+            candidates,
+            encoding,
+            entry_set,
+            id: id.to_vec(),
+        })
+    }
+
+    pub fn cast<R: CryptoRng + ?Sized>(
+        &self,
+        voter: &[u8],
+        choice: usize,
+        rng: &mut R,
+    ) -> Result<Ballot<S, D>, VotingError> {
+        if choice >= self.candidates {
+            return Err(VotingError::InvalidChoice);
+        }
+        let key = &self.key;
+        let context = self.context(voter);
+
+        let ballot = match self.encoding {
+            BallotEncoding::OneHot => {
+                let encryptions: Vec<_> = (0..self.candidates)
+                    .map(|i| key.encrypt(&Uint::from_u8((i == choice) as u8), rng))
+                    .collect();
+                let proofs = encryptions
+                    .iter()
+                    .enumerate()
+                    .map(|(i, encryption)| MembershipProof::prove_bit(key, &context, i == choice, encryption, rng))
+                    .collect();
+                let sum = encryptions
+                    .iter()
+                    .skip(1)
+                    .fold(encryptions[0], |(c_acc, r_acc), (c, r)| {
+                        (key.ciphertext_add(&c_acc, c), key.nonce_add(&r_acc, r))
+                    });
+                let sum_proof = MembershipProof::prove(key, &context, &[Uint::ONE], 0, &sum, rng);
+
+                Ballot {
+                    voter: voter.to_vec(),
+                    ciphertexts: encryptions.iter().map(|(c, _)| *c).collect(),
+                    proofs,
+                    sum_proof: Some(sum_proof),
+                }
+            }
+            BallotEncoding::Packed { .. } => {
+                let encryption = key.encrypt(&self.entry_set[choice], rng);
+                let proof = MembershipProof::prove(key, &context, &self.entry_set, choice, &encryption, rng);
+
+                Ballot {
+                    voter: voter.to_vec(),
+                    ciphertexts: vec![encryption.0],
+                    proofs: vec![proof],
+                    sum_proof: None,
+                }
+            }
+        };
+
+        Ok(ballot)
+    }
+
+    pub fn verify_ballot(&self, ballot: &Ballot<S, D>) -> bool {
+        if !self.is_well_formed(ballot) {
+            return false;
+        }
+
+        let context = self.context(&ballot.voter);
+        let entries_are_valid = ballot
+            .ciphertexts
+            .iter()
+            .zip(&ballot.proofs)
+            .all(|(c, proof)| proof.verify(&self.key, &context, &self.entry_set, c));
+        let sum_is_valid = match (&ballot.sum_proof, self.encoding) {
+            (Some(proof), BallotEncoding::OneHot) => proof.verify(&self.key, &context, &[Uint::ONE], &self.sum(ballot)),
+            (None, BallotEncoding::Packed { .. }) => true,
+            _ => false,
+        };
+
+        entries_are_valid && sum_is_valid
+    }
+
+    // checks all ballot proofs at once, on failure the ballots have to be verified one by one to find the culprit
+    pub fn verify_ballots<R: CryptoRng + ?Sized>(&self, ballots: &[Ballot<S, D>], rng: &mut R) -> bool {
+        if !ballots.iter().all(|ballot| self.is_well_formed(ballot)) {
+            return false;
+        }
+
+        let ciphertexts: Vec<_> = ballots.iter().flat_map(|ballot| ballot.ciphertexts.clone()).collect();
+        let proofs: Vec<_> = ballots.iter().flat_map(|ballot| ballot.proofs.clone()).collect();
+        let ballot_contexts: Vec<_> = ballots.iter().map(|ballot| self.context(&ballot.voter)).collect();
+        let contexts: Vec<_> = ballot_contexts
+            .iter()
+            .flat_map(|context| std::iter::repeat_n(context.as_slice(), self.width()))
+            .collect();
+        if !MembershipProof::verify_batch(&self.key, &self.entry_set, &contexts, &ciphertexts, &proofs, rng) {
+            return false;
+        }
+
+        match self.encoding {
+            BallotEncoding::OneHot => {
+                let sums: Vec<_> = ballots.iter().map(|ballot| self.sum(ballot)).collect();
+                let sum_proofs: Vec<_> = ballots.iter().filter_map(|ballot| ballot.sum_proof.clone()).collect();
+                let sum_contexts: Vec<_> = ballot_contexts.iter().map(|context| context.as_slice()).collect();
+                MembershipProof::verify_batch(&self.key, &[Uint::ONE], &sum_contexts, &sums, &sum_proofs, rng)
+            }
+            BallotEncoding::Packed { .. } => ballots.iter().all(|ballot| ballot.sum_proof.is_none()),
+        }
+    }
+
+    // ballots are only counted once their proofs verify, the first invalid one is reported
+    pub fn tally<R: CryptoRng + ?Sized>(&self, ballots: &[Ballot<S, D>], rng: &mut R) -> Result<Tally<D>, VotingError> {
+        if let Some(i) = ballots.iter().position(|ballot| !self.is_well_formed(ballot)) {
+            return Err(VotingError::InvalidBallot(i));
+        }
+        let mut voters = HashSet::new();
+        let mut ciphertexts = HashSet::new();
+        for (i, ballot) in ballots.iter().enumerate() {
+            if !voters.insert(ballot.voter.as_slice()) || !ballot.ciphertexts.iter().all(|c| ciphertexts.insert(*c)) {
+                return Err(VotingError::DuplicateBallot(i));
+            }
+        }
+        if let BallotEncoding::Packed { base } = self.encoding {
+            if ballots.len() >= base as usize {
+                return Err(VotingError::TooManyVoters);
+            }
+        }
+        if !self.verify_ballots(ballots, rng) {
+            let i = ballots.iter().position(|ballot| !self.verify_ballot(ballot));
+            return Err(VotingError::InvalidBallot(i.unwrap_or(0)));
+        }
+
+        // 1 is the trivial encryption of 0 with nonce 1
+        let mut ciphertexts = vec![NonZero::ONE; self.width()];
+        for ballot in ballots {
+            for (acc, c) in ciphertexts.iter_mut().zip(&ballot.ciphertexts) {
+                *acc = self.key.ciphertext_add(acc, c);
+            }
+        }
+
+        Ok(Tally {
+            ciphertexts,
+            voters: ballots.len(),
+        })
+    }
+
+    pub fn verify_tally_decryption(&self, tally: &Tally<D>, decryption: &TallyDecryption<S, D>) -> bool {
+        tally.ciphertexts.len() == self.width()
+            && decryption.plaintexts.len() == self.width()
+            && decryption.proofs.len() == self.width()
+            && tally
+                .ciphertexts
+                .iter()
+                .zip(&decryption.plaintexts)
+                .zip(&decryption.proofs)
+                .all(|((c, m), proof)| self.key.verify_decryption(c, m, proof))
+    }
+
+    // plaintexts may come from any decryption, e.g. a threshold one, and are turned into per candidate counts
+    pub fn decode(&self, tally: &Tally<D>, plaintexts: &[Uint<S>]) -> Result<Vec<u64>, VotingError> {
+        if plaintexts.len() != self.width() {
+            return Err(VotingError::MalformedTally);
+        }
+        let voters = tally.voters as u64;
+
+        let counts = match self.encoding {
+            BallotEncoding::OneHot => plaintexts
+                .iter()
+                .map(|m| to_u64(m).filter(|&count| count <= voters))
+                .collect::<Option<Vec<_>>>()
+                .ok_or(VotingError::MalformedTally)?,
+            BallotEncoding::Packed { base } => {
+                let base = NonZero::new(Uint::<S>::from_u32(base)).expect("base is at least 2");
+                let mut rest = plaintexts[0];
+                let mut counts = Vec::with_capacity(self.candidates);
+                for _ in 0..self.candidates {
+                    let (quotient, digit) = rest.div_rem(&base);
+                    counts.push(to_u64(&digit).expect("digit is below the base"));
+                    rest = quotient;
+                }
+                if rest != Uint::ZERO {
+                    return Err(VotingError::MalformedTally);
+                }
+
+                counts
+            }
+        };
+
+        if counts.iter().sum::<u64>() != voters {
+            return Err(VotingError::MalformedTally);
+        }
+        Ok(counts)
+    }
+
+    fn width(&self) -> usize {
+        match self.encoding {
+            BallotEncoding::OneHot => self.candidates,
+            BallotEncoding::Packed { .. } => 1,
+        }
+    }
+
+    fn is_well_formed(&self, ballot: &Ballot<S, D>) -> bool {
+        ballot.ciphertexts.len() == self.width()
+            && ballot.proofs.len() == self.width()
+            && ballot.sum_proof.is_some() == (self.encoding == BallotEncoding::OneHot)
+    }
+
+    // the election id is length prefixed so that (id, voter) pairs cannot collide
+    fn context(&self, voter: &[u8]) -> Vec<u8> {
+        let mut context = (self.id.len() as u64).to_be_bytes().to_vec();
+        context.extend_from_slice(&self.id);
+        context.extend_from_slice(voter);
+        context
+    }
+
+    fn sum(&self, ballot: &Ballot<S, D>) -> NonZero<Uint<D>> {
+        ballot
+            .ciphertexts
+            .iter()
+            .skip(1)
+            .fold(ballot.ciphertexts[0], |acc, c| self.key.ciphertext_add(&acc, c))
+    }
+}
+
+impl<
+    const H: usize,
+    const H_UNSAT: usize,
+    const S: usize,
+    const S_UNSAT: usize,
+    const D: usize,
+    const D_UNSAT: usize,
+    const Q: usize,
+> Election<S, D>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn decrypt_tally<R: CryptoRng + ?Sized>(
+        &self,
+        sk: &SecretKey<H, S, D>,
+        tally: &Tally<D>,
+        rng: &mut R,
+    ) -> Result<TallyDecryption<S, D>, VotingError> {
+        let (plaintexts, proofs) = tally
+            .ciphertexts
+            .iter()
+            .map(|c| sk.prove_decryption(c, DecryptionProofMode::ZeroKnowledge, rng))
+            .collect::<Option<(Vec<_>, Vec<_>)>>()
+            .ok_or(VotingError::MalformedTally)?;
+
+        Ok(TallyDecryption { plaintexts, proofs })
+    }
+}
+
+fn to_u64<const S: usize>(x: &Uint<S>) -> Option<u64> {
+    if x.bits() > u64::BITS {
+        return None;
+    }
+
+    let bytes = uint_to_be_bytes(x);
+    let mut low = [0u8; 8];
+    low.copy_from_slice(&bytes[bytes.len() - 8..]);
+    Some(u64::from_be_bytes(low))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sk::SecretKey;
+    use crate::voting::{BallotEncoding, Election, VotingError};
+    use crate::{EncryptionKey, KeyGenerator};
+    use crypto_bigint::{U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+
+    #[test]
+    fn should_tally_elections() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let choices = [0, 2, 2, 1, 2, 0];
+
+        for encoding in [BallotEncoding::OneHot, BallotEncoding::Packed { base: 16 }] {
+            let election = Election::new(pk, 3, encoding, b"election 1").unwrap();
+            let ballots: Vec<_> = choices
+                .iter()
+                .enumerate()
+                .map(|(i, &choice)| election.cast(&[i as u8], choice, &mut rng).unwrap())
+                .collect();
+            assert!(ballots.iter().all(|ballot| election.verify_ballot(ballot)));
+            assert!(election.verify_ballots(&ballots, &mut rng));
+
+            let tally = election.tally(&ballots, &mut rng).unwrap();
+            let decryption = election.decrypt_tally(&sk, &tally, &mut rng).unwrap();
+            assert!(election.verify_tally_decryption(&tally, &decryption));
+            assert_eq!(election.decode(&tally, &decryption.plaintexts).unwrap(), vec![2, 1, 3]);
+        }
+    }
+
+    #[test]
+    fn should_reject_invalid_ballots() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (_, pk) = SmallSecretKey::random(&mut rng);
+        let election = Election::new(pk, 3, BallotEncoding::OneHot, b"election 1").unwrap();
+        assert_eq!(
+            election.cast(b"alice", 3, &mut rng).unwrap_err(),
+            VotingError::InvalidChoice
+        );
+
+        // a ballot voting twice, its entries are valid bits but the sum proof belongs to another ballot
+        let first = election.cast(b"alice", 0, &mut rng).unwrap();
+        let mut double = election.cast(b"bob", 1, &mut rng).unwrap();
+        double.ciphertexts[0] = first.ciphertexts[0];
+        double.proofs[0] = first.proofs[0].clone();
+        assert!(!election.verify_ballot(&double));
+        assert!(!election.verify_ballots(&[first, double], &mut rng));
+
+        // a ballot giving one candidate two votes under the proofs of an honest ballot is not counted
+        let alice = election.cast(b"alice", 0, &mut rng).unwrap();
+        let mut forged = election.cast(b"bob", 1, &mut rng).unwrap();
+        forged.ciphertexts[1] = pk.encrypt(&U512::from_u8(2), &mut rng).0;
+        assert_eq!(
+            election.tally(&[alice, forged], &mut rng).unwrap_err(),
+            VotingError::InvalidBallot(1)
+        );
+
+        let packed = Election::new(pk, 3, BallotEncoding::Packed { base: 2 }, b"election 1").unwrap();
+        let ballots: Vec<_> = (0..2).map(|i| packed.cast(&[i], 0, &mut rng).unwrap()).collect();
+        assert_eq!(
+            packed.tally(&ballots, &mut rng).unwrap_err(),
+            VotingError::TooManyVoters
+        );
+        assert_eq!(
+            Election::new(pk, 600, BallotEncoding::Packed { base: 2 }, b"election 1").unwrap_err(),
+            VotingError::ModulusTooShort
+        );
+    }
+
+    #[test]
+    fn should_reject_replayed_ballots() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (_, pk) = SmallSecretKey::random(&mut rng);
+        let election = Election::new(pk, 3, BallotEncoding::OneHot, b"election 1").unwrap();
+        let other = Election::new(pk, 3, BallotEncoding::OneHot, b"election 2").unwrap();
+
+        let alice = election.cast(b"alice", 2, &mut rng).unwrap();
+        let bob = election.cast(b"bob", 1, &mut rng).unwrap();
+
+        // a copied ballot claimed by another voter or moved to another election no longer verifies
+        let mut stolen = alice.clone();
+        stolen.voter = b"carol".to_vec();
+        assert!(!election.verify_ballot(&stolen));
+        assert!(!election.verify_ballots(&[alice.clone(), bob.clone(), stolen], &mut rng));
+        assert!(!other.verify_ballot(&alice));
+
+        // the same ballot counted twice, and a ballot reusing another's ciphertexts
+        assert_eq!(
+            election
+                .tally(&[alice.clone(), bob.clone(), alice.clone()], &mut rng)
+                .unwrap_err(),
+            VotingError::DuplicateBallot(2)
+        );
+        let mut echo = alice.clone();
+        echo.voter = b"carol".to_vec();
+        assert_eq!(
+            election.tally(&[alice, bob, echo], &mut rng).unwrap_err(),
+            VotingError::DuplicateBallot(2)
+        );
+    }
+}
@@ -0,0 +1,27 @@
+#[cfg(feature = "mta")]
+mod aff_g;
+mod decryption;
+mod equality;
+#[cfg(feature = "curve")]
+mod log_star;
+mod membership;
+mod prm;
+mod transcript;
+
+#[cfg(feature = "mta")]
+pub use crate::zk::aff_g::{AffineProof, AffineStatement, AffineWitness};
+pub use crate::zk::decryption::{DecryptionProof, DecryptionProofMode, NthRootProof};
+pub use crate::zk::equality::PlaintextEqualityProof;
+#[cfg(feature = "curve")]
+pub use crate::zk::log_star::LogStarProof;
+pub use crate::zk::membership::MembershipProof;
+pub use crate::zk::prm::RingPedersenProof;
+
+#[cfg(feature = "mta")]
+pub(crate) use crate::zk::aff_g::ell_prime;
+pub(crate) use crate::zk::transcript::Transcript;
+
+#[cfg(feature = "k256")]
+pub type Secp256k1LogStarProof<const S: usize, const D: usize> = LogStarProof<k256::Secp256k1, S, D>;
+#[cfg(feature = "p256")]
+pub type P256LogStarProof<const S: usize, const D: usize> = LogStarProof<p256::NistP256, S, D>;
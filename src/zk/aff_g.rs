@@ -0,0 +1,216 @@
+use crate::curve::{CurveRanges, append_point, curve_order, uint_to_scalar};
+use crate::pk::PublicKey;
+use crate::ring_pedersen::RingPedersenParams;
+use crate::traits::{EncryptionKey, HomomorphicKey, Key};
+use crate::zk::Transcript;
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, RandomBits, Split, Uint};
+use elliptic_curve::CurveArithmetic;
+use elliptic_curve::ops::MulByGenerator;
+use rand_core::CryptoRng;
+use subtle::{ConstantTimeEq, ConstantTimeLess};
+
+// Paillier affine operation with group commitment in range (CGGMP21, figure 15), with the masks sampled from
+// non negative ranges; the prover owns `prover_key` and shows that D = C^x * Enc_0(y; rho), Y = Enc_1(y; rho_y)
+// and X = x * G for x < 2^ell and y < 2^ell'
+pub struct AffineStatement<'a, C: CurveArithmetic, const S: usize, const D: usize> {
+    pub verifier_key: &'a PublicKey<S, D>,
+    pub prover_key: &'a PublicKey<S, D>,
+    pub setup: &'a RingPedersenParams<S>,
+    pub c: &'a NonZero<Uint<D>>,
+    pub d: &'a NonZero<Uint<D>>,
+    pub y: &'a NonZero<Uint<D>>,
+    pub x: &'a C::ProjectivePoint,
+}
+
+pub struct AffineWitness<'a, const S: usize> {
+    pub x: &'a Uint<S>,
+    pub y: &'a Uint<S>,
+    pub rho: &'a NonZero<Uint<S>>,
+    pub rho_y: &'a NonZero<Uint<S>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct AffineProof<C: CurveArithmetic, const S: usize, const D: usize> {
+    a: NonZero<Uint<D>>,
+    b_x: C::ProjectivePoint,
+    b_y: NonZero<Uint<D>>,
+    commitment_alpha: Uint<S>,
+    commitment_x: Uint<S>,
+    commitment_beta: Uint<S>,
+    commitment_y: Uint<S>,
+    z1: Uint<S>,
+    z2: Uint<S>,
+    z3: Uint<D>,
+    z4: Uint<D>,
+    w: NonZero<Uint<S>>,
+    w_y: NonZero<Uint<S>>,
+}
+
+// length of the additive share y, it statistically hides x * c for x, c < 2^ell
+pub(crate) fn ell_prime(ranges: &CurveRanges) -> u32 {
+    5 * ranges.ell
+}
+
+impl<C, const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize>
+    AffineStatement<'_, C, S, D>
+where
+    C: CurveArithmetic,
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    fn transcript(&self) -> Transcript {
+        let mut transcript = Transcript::new(b"paillier-affine-group-commitment");
+        transcript.append_uint(b"verifier-n", self.verifier_key.n.as_ref());
+        transcript.append_uint(b"prover-n", self.prover_key.n.as_ref());
+        self.setup.append_to(&mut transcript);
+        transcript.append_uint(b"c", self.c.as_ref());
+        transcript.append_uint(b"d", self.d.as_ref());
+        transcript.append_uint(b"y", self.y.as_ref());
+        append_point::<C>(&mut transcript, b"x", self.x);
+        transcript
+    }
+
+    fn is_well_formed(&self, ranges: &CurveRanges) -> bool {
+        let verifier_key = self.verifier_key;
+        let prover_key = self.prover_key;
+
+        ranges.fits(&verifier_key.n, ell_prime(ranges))
+            && ranges.fits(&prover_key.n, ell_prime(ranges))
+            && (verifier_key.ciphertext_is_valid(self.c)
+                & verifier_key.ciphertext_is_valid(self.d)
+                & prover_key.ciphertext_is_valid(self.y))
+            .into()
+    }
+}
+
+impl<C, const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> AffineProof<C, S, D>
+where
+    C: CurveArithmetic,
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn prove<R: CryptoRng + ?Sized>(
+        statement: &AffineStatement<'_, C, S, D>,
+        witness: &AffineWitness<'_, S>,
+        rng: &mut R,
+    ) -> Self {
+        let ranges = CurveRanges::new::<C, S>();
+        assert!(
+            statement.is_well_formed(&ranges),
+            "paillier moduli are too short for the curve"
+        );
+        let verifier_key = statement.verifier_key;
+        let prover_key = statement.prover_key;
+        let setup = statement.setup;
+        let setup_bits = setup.n.bits();
+
+        let alpha = Uint::<S>::random_bits(rng, ranges.ell + ranges.epsilon);
+        let beta = Uint::<S>::random_bits(rng, ell_prime(&ranges) + ranges.epsilon);
+        let r = verifier_key.random_nonce(rng);
+        let r_y = prover_key.random_nonce(rng);
+        let gamma = Uint::<D>::random_bits(rng, ranges.ell + ranges.epsilon + setup_bits);
+        let m = Uint::<D>::random_bits(rng, ranges.ell + setup_bits);
+        let delta = Uint::<D>::random_bits(rng, ranges.ell + ranges.epsilon + setup_bits);
+        let mu = Uint::<D>::random_bits(rng, ranges.ell + setup_bits);
+
+        let a = verifier_key.ciphertext_add(
+            &verifier_key.ciphertext_mul_scalar(statement.c, &alpha),
+            &verifier_key.encrypt_with_nonce(&beta, &r),
+        );
+        let b_x = C::ProjectivePoint::mul_by_generator(&uint_to_scalar::<C, S>(&alpha));
+        let b_y = prover_key.encrypt_with_nonce(&beta, &r_y);
+        let commitment_alpha = setup.commit_with_randomness(&alpha, &gamma);
+        let commitment_x = setup.commit_with_randomness(witness.x, &m);
+        let commitment_beta = setup.commit_with_randomness(&beta, &delta);
+        let commitment_y = setup.commit_with_randomness(witness.y, &mu);
+
+        let mut proof = AffineProof {
+            a,
+            b_x,
+            b_y,
+            commitment_alpha,
+            commitment_x,
+            commitment_beta,
+            commitment_y,
+            z1: Uint::ZERO,
+            z2: Uint::ZERO,
+            z3: Uint::ZERO,
+            z4: Uint::ZERO,
+            w: r,
+            w_y: r_y,
+        };
+        let e = proof.challenge(statement);
+        let e_wide = e.resize::<D>();
+
+        proof.z1 = alpha.wrapping_add(&e.wrapping_mul(witness.x));
+        proof.z2 = beta.wrapping_add(&e.wrapping_mul(witness.y));
+        proof.z3 = gamma.wrapping_add(&e_wide.wrapping_mul(&m));
+        proof.z4 = delta.wrapping_add(&e_wide.wrapping_mul(&mu));
+        proof.w = verifier_key.nonce_add(&r, &verifier_key.nonce_mul_scalar(witness.rho, &e));
+        proof.w_y = prover_key.nonce_add(&r_y, &prover_key.nonce_mul_scalar(witness.rho_y, &e));
+
+        proof
+    }
+
+    pub fn verify(&self, statement: &AffineStatement<'_, C, S, D>) -> bool {
+        let ranges = CurveRanges::new::<C, S>();
+        if !statement.is_well_formed(&ranges) {
+            return false;
+        }
+        let verifier_key = statement.verifier_key;
+        let prover_key = statement.prover_key;
+        let setup = statement.setup;
+
+        let is_well_formed = verifier_key.ciphertext_is_valid(&self.a)
+            & prover_key.ciphertext_is_valid(&self.b_y)
+            & verifier_key.nonce_is_valid(&self.w)
+            & prover_key.nonce_is_valid(&self.w_y)
+            & self.z1.ct_lt(&Uint::ONE.shl_vartime(ranges.ell + ranges.epsilon + 1))
+            & self
+                .z2
+                .ct_lt(&Uint::ONE.shl_vartime(ell_prime(&ranges) + ranges.epsilon + 1));
+        if !bool::from(is_well_formed) {
+            return false;
+        }
+
+        let e = self.challenge(statement);
+
+        let affine_lhs = verifier_key.ciphertext_add(
+            &verifier_key.ciphertext_mul_scalar(statement.c, &self.z1),
+            &verifier_key.encrypt_with_nonce(&self.z2, &self.w),
+        );
+        let affine_rhs = verifier_key.ciphertext_add(&self.a, &verifier_key.ciphertext_mul_scalar(statement.d, &e));
+
+        let group_lhs = C::ProjectivePoint::mul_by_generator(&uint_to_scalar::<C, S>(&self.z1));
+        let group_rhs = self.b_x + *statement.x * uint_to_scalar::<C, S>(&e);
+
+        let encryption_lhs = prover_key.encrypt_with_nonce(&self.z2, &self.w_y);
+        let encryption_rhs = prover_key.ciphertext_add(&self.b_y, &prover_key.ciphertext_mul_scalar(statement.y, &e));
+
+        let result = affine_lhs.ct_eq(&affine_rhs)
+            & group_lhs.ct_eq(&group_rhs)
+            & encryption_lhs.ct_eq(&encryption_rhs)
+            & setup.commitment_eq(&self.z1, &self.z3, &self.commitment_alpha, &self.commitment_x, &e)
+            & setup.commitment_eq(&self.z2, &self.z4, &self.commitment_beta, &self.commitment_y, &e);
+        result.into()
+    }
+
+    fn challenge(&self, statement: &AffineStatement<'_, C, S, D>) -> Uint<S> {
+        let mut transcript = statement.transcript();
+        transcript.append_uint(b"a", self.a.as_ref());
+        append_point::<C>(&mut transcript, b"b-x", &self.b_x);
+        transcript.append_uint(b"b-y", self.b_y.as_ref());
+        transcript.append_uint(b"commitment-alpha", &self.commitment_alpha);
+        transcript.append_uint(b"commitment-x", &self.commitment_x);
+        transcript.append_uint(b"commitment-beta", &self.commitment_beta);
+        transcript.append_uint(b"commitment-y", &self.commitment_y);
+        transcript.challenge_mod(b"e", &curve_order::<C, S>())
+    }
+}
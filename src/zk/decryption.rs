@@ -0,0 +1,164 @@
+use crate::pk::PublicKey;
+use crate::sk::SecretKey;
+use crate::traits::{EncryptionKey, HomomorphicKey, Key, OpeningKey};
+use crate::zk::Transcript;
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, Split, Uint};
+use rand_core::CryptoRng;
+use subtle::ConstantTimeEq;
+
+// the challenge has to stay below the smallest prime factor of n for the nth root proof to be sound
+const CHALLENGE_BITS: u32 = 128;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DecryptionProofMode {
+    RevealNonce,
+    ZeroKnowledge,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum DecryptionProof<const S: usize, const D: usize> {
+    // the nonce recovered by `OpeningKey::open`, the verifier re-encrypts the plaintext with it
+    Nonce(NonZero<Uint<S>>),
+    // proof of knowledge of an nth root of c * (1 + n)^(-m) mod n^2
+    ZeroKnowledge(NthRootProof<S, D>),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct NthRootProof<const S: usize, const D: usize> {
+    a: NonZero<Uint<D>>,
+    z: NonZero<Uint<S>>,
+}
+
+impl<
+    const H: usize,
+    const H_UNSAT: usize,
+    const S: usize,
+    const S_UNSAT: usize,
+    const D: usize,
+    const D_UNSAT: usize,
+    const Q: usize,
+> SecretKey<H, S, D>
+where
+    Uint<H>: Concat<Output = Uint<S>>,
+    Odd<Uint<H>>: PrecomputeInverter<Inverter = SafeGcdInverter<H, H_UNSAT>>,
+    Uint<S>: Split<Output = Uint<H>> + Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn prove_decryption<R: CryptoRng + ?Sized>(
+        &self,
+        c: &NonZero<Uint<D>>,
+        mode: DecryptionProofMode,
+        rng: &mut R,
+    ) -> Option<(Uint<S>, DecryptionProof<S, D>)> {
+        let (m, r) = Option::from(self.try_open(c))?;
+
+        let proof = match mode {
+            DecryptionProofMode::RevealNonce => DecryptionProof::Nonce(r),
+            DecryptionProofMode::ZeroKnowledge => {
+                let pk = &self.pk;
+                let s = pk.random_nonce(rng);
+                let a = pk.encrypt_with_nonce(&Uint::ZERO, &s);
+                let e = challenge(pk, c, &m, &a);
+                let z = pk.nonce_add(&s, &pk.nonce_mul_scalar(&r, &e));
+
+                DecryptionProof::ZeroKnowledge(NthRootProof { a, z })
+            }
+        };
+
+        Some((m, proof))
+    }
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> PublicKey<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    pub fn verify_decryption(&self, c: &NonZero<Uint<D>>, m: &Uint<S>, proof: &DecryptionProof<S, D>) -> bool {
+        if !bool::from(self.ciphertext_is_valid(c) & self.plaintext_is_valid(m)) {
+            return false;
+        }
+
+        match proof {
+            DecryptionProof::Nonce(r) => {
+                let result = self.nonce_is_valid(r) & self.encrypt_with_nonce(m, r).ct_eq(c);
+                result.into()
+            }
+            DecryptionProof::ZeroKnowledge(NthRootProof { a, z }) => {
+                if !bool::from(self.ciphertext_is_valid(a) & self.nonce_is_valid(z)) {
+                    return false;
+                }
+
+                // z^n == a * u^e mod n^2 for u = c * (1 + n)^(-m)
+                let e = challenge(self, c, m, a);
+                let u = self.ciphertext_sub_plain(c, m);
+                let lhs = self.encrypt_with_nonce(&Uint::ZERO, z);
+                let rhs = self.ciphertext_add(a, &self.ciphertext_mul_scalar(&u, &e));
+                lhs.ct_eq(&rhs).into()
+            }
+        }
+    }
+}
+
+fn challenge<const S: usize, const D: usize>(
+    pk: &PublicKey<S, D>,
+    c: &NonZero<Uint<D>>,
+    m: &Uint<S>,
+    a: &NonZero<Uint<D>>,
+) -> Uint<S> {
+    let mut transcript = Transcript::new(b"paillier-decryption");
+    transcript.append_uint(b"n", pk.n.as_ref());
+    transcript.append_uint(b"c", c.as_ref());
+    transcript.append_uint(b"m", m);
+    transcript.append_uint(b"a", a.as_ref());
+    transcript.challenge_bits(b"e", CHALLENGE_BITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sk::SecretKey;
+    use crate::zk::{DecryptionProof, DecryptionProofMode};
+    use crate::{EncryptionKey, KeyGenerator};
+    use crypto_bigint::{U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+
+    #[test]
+    fn should_prove_and_verify_decryption() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let m = pk.random_plaintext(&mut rng);
+        let (c, _) = pk.encrypt(&m, &mut rng);
+
+        for mode in [DecryptionProofMode::RevealNonce, DecryptionProofMode::ZeroKnowledge] {
+            let (m2, proof) = sk.prove_decryption(&c, mode, &mut rng).unwrap();
+            assert_eq!(m, m2);
+            assert!(pk.verify_decryption(&c, &m, &proof));
+            assert!(!pk.verify_decryption(&c, &m.wrapping_add(&U512::ONE), &proof));
+        }
+    }
+
+    #[test]
+    fn should_reject_proof_for_other_ciphertext() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, pk) = SmallSecretKey::random(&mut rng);
+        let m = pk.random_plaintext(&mut rng);
+        let (c, _) = pk.encrypt(&m, &mut rng);
+        let (other, _) = pk.encrypt(&m, &mut rng);
+
+        let (_, proof) = sk
+            .prove_decryption(&c, DecryptionProofMode::ZeroKnowledge, &mut rng)
+            .unwrap();
+        assert!(matches!(proof, DecryptionProof::ZeroKnowledge(_)));
+        assert!(!pk.verify_decryption(&other, &m, &proof));
+    }
+}
@@ -0,0 +1,173 @@
+use crate::pk::PublicKey;
+use crate::traits::{EncryptionKey, HomomorphicKey, Key};
+use crate::zk::Transcript;
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, RandomBits, Split, Uint};
+use rand_core::CryptoRng;
+use subtle::{ConstantTimeEq, ConstantTimeLess};
+
+const CHALLENGE_BITS: u32 = 128;
+const STATISTICAL_SECURITY_BITS: u32 = 128;
+
+// equality of the plaintexts of c1 = Enc_1(x; r1) and c2 = Enc_2(x; r2) for x < 2^range_bits, the response z is an
+// integer so the proof is sound modulo neither key; the range guarantee has the usual slack, an accepted proof only
+// shows x < 2^(range_bits + statistical security + challenge bits + 1)
+#[derive(Debug, Copy, Clone)]
+pub struct PlaintextEqualityProof<const S1: usize, const D1: usize, const S2: usize, const D2: usize> {
+    a1: NonZero<Uint<D1>>,
+    a2: NonZero<Uint<D2>>,
+    z: Uint<S1>,
+    w1: NonZero<Uint<S1>>,
+    w2: NonZero<Uint<S2>>,
+}
+
+impl<
+    const S1: usize,
+    const S1_UNSAT: usize,
+    const D1: usize,
+    const D1_UNSAT: usize,
+    const Q1: usize,
+    const S2: usize,
+    const S2_UNSAT: usize,
+    const D2: usize,
+    const D2_UNSAT: usize,
+    const Q2: usize,
+> PlaintextEqualityProof<S1, D1, S2, D2>
+where
+    Uint<S1>: Concat<Output = Uint<D1>>,
+    Odd<Uint<S1>>: PrecomputeInverter<Inverter = SafeGcdInverter<S1, S1_UNSAT>>,
+    Uint<D1>: Split<Output = Uint<S1>> + Concat<Output = Uint<Q1>>,
+    Odd<Uint<D1>>: PrecomputeInverter<Inverter = SafeGcdInverter<D1, D1_UNSAT>>,
+    Uint<Q1>: Split<Output = Uint<D1>>,
+    Uint<S2>: Concat<Output = Uint<D2>>,
+    Odd<Uint<S2>>: PrecomputeInverter<Inverter = SafeGcdInverter<S2, S2_UNSAT>>,
+    Uint<D2>: Split<Output = Uint<S2>> + Concat<Output = Uint<Q2>>,
+    Odd<Uint<D2>>: PrecomputeInverter<Inverter = SafeGcdInverter<D2, D2_UNSAT>>,
+    Uint<Q2>: Split<Output = Uint<D2>>,
+{
+    // encryptions are the (ciphertext, nonce) pairs returned by `EncryptionKey::encrypt` for the plaintext x
+    pub fn prove<R: CryptoRng + ?Sized>(
+        key1: &PublicKey<S1, D1>,
+        key2: &PublicKey<S2, D2>,
+        range_bits: u32,
+        x: &Uint<S1>,
+        encryption1: &(NonZero<Uint<D1>>, NonZero<Uint<S1>>),
+        encryption2: &(NonZero<Uint<D2>>, NonZero<Uint<S2>>),
+        rng: &mut R,
+    ) -> Self {
+        assert!(
+            fits(key1, key2, range_bits),
+            "paillier moduli are too short for the range"
+        );
+        assert!(x.bits() <= range_bits, "plaintext is out of range");
+        let (c1, r1) = encryption1;
+        let (c2, r2) = encryption2;
+
+        let alpha = Uint::<S1>::random_bits(rng, range_bits + STATISTICAL_SECURITY_BITS + CHALLENGE_BITS);
+        let s1 = key1.random_nonce(rng);
+        let s2 = key2.random_nonce(rng);
+        let a1 = key1.encrypt_with_nonce(&alpha, &s1);
+        let a2 = key2.encrypt_with_nonce(&alpha.resize(), &s2);
+
+        let e = challenge(key1, key2, range_bits, c1, c2, &a1, &a2);
+        let e2 = e.resize::<S2>();
+        let z = alpha.wrapping_add(&e.wrapping_mul(x));
+        let w1 = key1.nonce_add(&s1, &key1.nonce_mul_scalar(r1, &e));
+        let w2 = key2.nonce_add(&s2, &key2.nonce_mul_scalar(r2, &e2));
+
+        PlaintextEqualityProof { a1, a2, z, w1, w2 }
+    }
+
+    pub fn verify(
+        &self,
+        key1: &PublicKey<S1, D1>,
+        key2: &PublicKey<S2, D2>,
+        range_bits: u32,
+        c1: &NonZero<Uint<D1>>,
+        c2: &NonZero<Uint<D2>>,
+    ) -> bool {
+        if !fits(key1, key2, range_bits) {
+            return false;
+        }
+
+        let z_bound = Uint::ONE.shl_vartime(range_bits + STATISTICAL_SECURITY_BITS + CHALLENGE_BITS + 1);
+        let is_well_formed = key1.ciphertext_is_valid(c1)
+            & key2.ciphertext_is_valid(c2)
+            & key1.ciphertext_is_valid(&self.a1)
+            & key2.ciphertext_is_valid(&self.a2)
+            & key1.nonce_is_valid(&self.w1)
+            & key2.nonce_is_valid(&self.w2)
+            & self.z.ct_lt(&z_bound);
+        if !bool::from(is_well_formed) {
+            return false;
+        }
+
+        let e = challenge(key1, key2, range_bits, c1, c2, &self.a1, &self.a2);
+        let lhs1 = key1.encrypt_with_nonce(&self.z, &self.w1);
+        let rhs1 = key1.ciphertext_add(&self.a1, &key1.ciphertext_mul_scalar(c1, &e));
+        let lhs2 = key2.encrypt_with_nonce(&self.z.resize(), &self.w2);
+        let rhs2 = key2.ciphertext_add(&self.a2, &key2.ciphertext_mul_scalar(c2, &e.resize()));
+
+        (lhs1.ct_eq(&rhs1) & lhs2.ct_eq(&rhs2)).into()
+    }
+}
+
+fn fits<const S1: usize, const D1: usize, const S2: usize, const D2: usize>(
+    key1: &PublicKey<S1, D1>,
+    key2: &PublicKey<S2, D2>,
+    range_bits: u32,
+) -> bool {
+    // z has to be a valid plaintext under both keys
+    let z_bits = range_bits + STATISTICAL_SECURITY_BITS + CHALLENGE_BITS + 1;
+    key1.n.bits() > z_bits && key2.n.bits() > z_bits
+}
+
+fn challenge<const S1: usize, const D1: usize, const S2: usize, const D2: usize>(
+    key1: &PublicKey<S1, D1>,
+    key2: &PublicKey<S2, D2>,
+    range_bits: u32,
+    c1: &NonZero<Uint<D1>>,
+    c2: &NonZero<Uint<D2>>,
+    a1: &NonZero<Uint<D1>>,
+    a2: &NonZero<Uint<D2>>,
+) -> Uint<S1> {
+    let mut transcript = Transcript::new(b"paillier-plaintext-equality");
+    transcript.append_uint(b"n1", key1.n.as_ref());
+    transcript.append_uint(b"n2", key2.n.as_ref());
+    transcript.append_bytes(b"range-bits", &range_bits.to_be_bytes());
+    transcript.append_uint(b"c1", c1.as_ref());
+    transcript.append_uint(b"c2", c2.as_ref());
+    transcript.append_uint(b"a1", a1.as_ref());
+    transcript.append_uint(b"a2", a2.as_ref());
+    transcript.challenge_bits(b"e", CHALLENGE_BITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sk::SecretKey;
+    use crate::zk::PlaintextEqualityProof;
+    use crate::{EncryptionKey, KeyGenerator, PaillierSecretKey2048};
+    use crypto_bigint::{RandomBits, U512, U1024, U2048};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type SecretKey1024 = SecretKey<{ U512::LIMBS }, { U1024::LIMBS }, { U2048::LIMBS }>;
+
+    #[test]
+    fn should_prove_equality_across_key_sizes() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (_, pk2048) = PaillierSecretKey2048::random(&mut rng);
+        let (_, pk1024) = SecretKey1024::random(&mut rng);
+
+        let x = U2048::random_bits(&mut rng, 256);
+        let encryption1 = pk2048.encrypt(&x, &mut rng);
+        let encryption2 = pk1024.encrypt(&x.resize::<{ U1024::LIMBS }>(), &mut rng);
+
+        let proof = PlaintextEqualityProof::prove(&pk2048, &pk1024, 256, &x, &encryption1, &encryption2, &mut rng);
+        assert!(proof.verify(&pk2048, &pk1024, 256, &encryption1.0, &encryption2.0));
+
+        let (other, _) = pk1024.encrypt(&x.resize::<{ U1024::LIMBS }>().wrapping_add(&U1024::ONE), &mut rng);
+        assert!(!proof.verify(&pk2048, &pk1024, 256, &encryption1.0, &other));
+        assert!(!proof.verify(&pk2048, &pk1024, 255, &encryption1.0, &encryption2.0));
+    }
+}
@@ -0,0 +1,173 @@
+use crate::curve::{CurveRanges, append_point, curve_order, scalar_to_uint, uint_to_scalar};
+use crate::pk::PublicKey;
+use crate::ring_pedersen::RingPedersenParams;
+use crate::traits::{EncryptionKey, HomomorphicKey, Key};
+use crate::zk::Transcript;
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, RandomBits, Split, Uint};
+use elliptic_curve::CurveArithmetic;
+use elliptic_curve::ops::MulByGenerator;
+use rand_core::CryptoRng;
+use subtle::{ConstantTimeEq, ConstantTimeLess};
+
+// knowledge of exponent vs paillier encryption (CGGMP21, figure 25) with the masks sampled from non negative ranges,
+// c = Enc(x; rho) and X = x * G for the standard generator G
+#[derive(Debug, Copy, Clone)]
+pub struct LogStarProof<C: CurveArithmetic, const S: usize, const D: usize> {
+    commitment_x: Uint<S>,
+    a: NonZero<Uint<D>>,
+    y: C::ProjectivePoint,
+    commitment_alpha: Uint<S>,
+    z1: Uint<S>,
+    z2: NonZero<Uint<S>>,
+    z3: Uint<D>,
+}
+
+impl<C, const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize>
+    LogStarProof<C, S, D>
+where
+    C: CurveArithmetic,
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    // encryption is the (ciphertext, nonce) pair returned by `EncryptionKey::encrypt` for the plaintext x
+    pub fn prove<R: CryptoRng + ?Sized>(
+        key: &PublicKey<S, D>,
+        setup: &RingPedersenParams<S>,
+        x: &C::Scalar,
+        encryption: &(NonZero<Uint<D>>, NonZero<Uint<S>>),
+        rng: &mut R,
+    ) -> Self {
+        let ranges = CurveRanges::new::<C, S>();
+        assert!(
+            ranges.fits(&key.n, ranges.ell),
+            "paillier modulus is too short for the curve"
+        );
+        let (c, rho) = encryption;
+        let x_uint = scalar_to_uint::<C, S>(x);
+        let x_point = C::ProjectivePoint::mul_by_generator(x);
+        let setup_bits = setup.n.bits();
+
+        let alpha = Uint::<S>::random_bits(rng, ranges.ell + ranges.epsilon);
+        let mu = Uint::<D>::random_bits(rng, ranges.ell + setup_bits);
+        let r = key.random_nonce(rng);
+        let gamma = Uint::<D>::random_bits(rng, ranges.ell + ranges.epsilon + setup_bits);
+
+        let mut proof = LogStarProof {
+            commitment_x: setup.commit_with_randomness(&x_uint, &mu),
+            a: key.encrypt_with_nonce(&alpha, &r),
+            y: C::ProjectivePoint::mul_by_generator(&uint_to_scalar::<C, S>(&alpha)),
+            commitment_alpha: setup.commit_with_randomness(&alpha, &gamma),
+            z1: Uint::ZERO,
+            z2: r,
+            z3: Uint::ZERO,
+        };
+        let e = proof.challenge(key, setup, c, &x_point);
+
+        proof.z1 = alpha.wrapping_add(&e.wrapping_mul(&x_uint));
+        proof.z2 = key.nonce_add(&r, &key.nonce_mul_scalar(rho, &e));
+        proof.z3 = gamma.wrapping_add(&e.resize::<D>().wrapping_mul(&mu));
+
+        proof
+    }
+
+    pub fn verify(
+        &self,
+        key: &PublicKey<S, D>,
+        setup: &RingPedersenParams<S>,
+        c: &NonZero<Uint<D>>,
+        x: &C::ProjectivePoint,
+    ) -> bool {
+        let ranges = CurveRanges::new::<C, S>();
+        if !ranges.fits(&key.n, ranges.ell) {
+            return false;
+        }
+
+        let is_well_formed = key.ciphertext_is_valid(c)
+            & key.ciphertext_is_valid(&self.a)
+            & key.nonce_is_valid(&self.z2)
+            & self.z1.ct_lt(&Uint::ONE.shl_vartime(ranges.ell + ranges.epsilon + 1));
+        if !bool::from(is_well_formed) {
+            return false;
+        }
+
+        let e = self.challenge(key, setup, c, x);
+
+        let encryption_lhs = key.encrypt_with_nonce(&self.z1, &self.z2);
+        let encryption_rhs = key.ciphertext_add(&self.a, &key.ciphertext_mul_scalar(c, &e));
+
+        let group_lhs = C::ProjectivePoint::mul_by_generator(&uint_to_scalar::<C, S>(&self.z1));
+        let group_rhs = self.y + *x * uint_to_scalar::<C, S>(&e);
+
+        let result = encryption_lhs.ct_eq(&encryption_rhs)
+            & group_lhs.ct_eq(&group_rhs)
+            & setup.commitment_eq(&self.z1, &self.z3, &self.commitment_alpha, &self.commitment_x, &e);
+        result.into()
+    }
+
+    fn challenge(
+        &self,
+        key: &PublicKey<S, D>,
+        setup: &RingPedersenParams<S>,
+        c: &NonZero<Uint<D>>,
+        x: &C::ProjectivePoint,
+    ) -> Uint<S> {
+        let mut transcript = Transcript::new(b"paillier-log-star");
+        transcript.append_uint(b"n", key.n.as_ref());
+        setup.append_to(&mut transcript);
+        transcript.append_uint(b"c", c.as_ref());
+        append_point::<C>(&mut transcript, b"x", x);
+        transcript.append_uint(b"commitment-x", &self.commitment_x);
+        transcript.append_uint(b"a", self.a.as_ref());
+        append_point::<C>(&mut transcript, b"y", &self.y);
+        transcript.append_uint(b"commitment-alpha", &self.commitment_alpha);
+        transcript.challenge_mod(b"e", &curve_order::<C, S>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::{random_scalar, scalar_to_uint};
+    use crate::ring_pedersen::RingPedersenSecret;
+    use crate::zk::LogStarProof;
+    use crate::{EncryptionKey, PaillierSecretKey2048};
+    use crypto_bigint::U2048;
+    use elliptic_curve::ops::MulByGenerator;
+    use elliptic_curve::{CurveArithmetic, Field};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    fn should_prove_and_verify_for_curve<C: CurveArithmetic>() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (sk, _) = PaillierSecretKey2048::builder().safe_primes(true).generate(&mut rng);
+        let pk = sk.as_public_key();
+        let setup = *RingPedersenSecret::from_secret_key(&sk, &mut rng).params();
+
+        let x = random_scalar::<C, _>(&mut rng);
+        let x_point = C::ProjectivePoint::mul_by_generator(&x);
+        let encryption = pk.encrypt(&scalar_to_uint::<C, { U2048::LIMBS }>(&x), &mut rng);
+
+        let proof = LogStarProof::<C, _, _>::prove(&pk, &setup, &x, &encryption, &mut rng);
+        assert!(proof.verify(&pk, &setup, &encryption.0, &x_point));
+
+        let other_point = x_point + C::ProjectivePoint::mul_by_generator(&C::Scalar::ONE);
+        assert!(!proof.verify(&pk, &setup, &encryption.0, &other_point));
+
+        let (other_c, _) = pk.encrypt(&U2048::ONE, &mut rng);
+        assert!(!proof.verify(&pk, &setup, &other_c, &x_point));
+    }
+
+    #[test]
+    fn should_prove_and_verify_on_secp256k1() {
+        should_prove_and_verify_for_curve::<k256::Secp256k1>();
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn should_prove_and_verify_on_p256() {
+        should_prove_and_verify_for_curve::<p256::NistP256>();
+    }
+}
@@ -0,0 +1,330 @@
+use crate::pk::PublicKey;
+use crate::traits::{EncryptionKey, HomomorphicKey, Key};
+use crate::utils::uint_to_be_bytes;
+use crate::zk::Transcript;
+use crypto_bigint::modular::SafeGcdInverter;
+use crypto_bigint::{Concat, NonZero, Odd, PrecomputeInverter, RandomBits, Split, Uint};
+use rand_core::CryptoRng;
+use subtle::{ConstantTimeEq, ConstantTimeLess};
+
+const CHALLENGE_BITS: u32 = 128;
+const BATCH_WEIGHT_BITS: u32 = 128;
+
+// disjunctive (Cramer-Damgard-Schoenmakers) proof that c encrypts one of the public values m_1..m_k, branch i shows
+// that c * (1 + n)^(-m_i) is an nth residue and the branch challenges add up to the transcript challenge mod 2^128;
+// the context (e.g. election and voter) is hashed into the challenge so that a proof cannot be replayed elsewhere
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MembershipProof<const S: usize, const D: usize> {
+    a: Vec<NonZero<Uint<D>>>,
+    e: Vec<Uint<S>>,
+    z: Vec<NonZero<Uint<S>>>,
+}
+
+impl<const S: usize, const S_UNSAT: usize, const D: usize, const D_UNSAT: usize, const Q: usize> MembershipProof<S, D>
+where
+    Uint<S>: Concat<Output = Uint<D>>,
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+    Uint<D>: Split<Output = Uint<S>> + Concat<Output = Uint<Q>>,
+    Odd<Uint<D>>: PrecomputeInverter<Inverter = SafeGcdInverter<D, D_UNSAT>>,
+    Uint<Q>: Split<Output = Uint<D>>,
+{
+    // encryption is the (ciphertext, nonce) pair of set[index], for aggregated ciphertexts the nonces are combined
+    // with `HomomorphicKey::nonce_add`
+    pub fn prove<R: CryptoRng + ?Sized>(
+        key: &PublicKey<S, D>,
+        context: &[u8],
+        set: &[Uint<S>],
+        index: usize,
+        encryption: &(NonZero<Uint<D>>, NonZero<Uint<S>>),
+        rng: &mut R,
+    ) -> Self {
+        assert!(index < set.len(), "plaintext index is out of the set");
+        let (c, r) = encryption;
+
+        let mut a = Vec::with_capacity(set.len());
+        let mut e = Vec::with_capacity(set.len());
+        let mut z = Vec::with_capacity(set.len());
+        let s = key.random_nonce(rng);
+        for (i, m) in set.iter().enumerate() {
+            if i == index {
+                a.push(key.encrypt_with_nonce(&Uint::ZERO, &s));
+                e.push(Uint::ZERO);
+                z.push(s);
+            } else {
+                // simulated branch, a_i = z_i^n * u_i^(-e_i)
+                let e_i = Uint::random_bits(rng, CHALLENGE_BITS);
+                let z_i = key.random_nonce(rng);
+                let u_i = key.ciphertext_sub_plain(c, m);
+                a.push(key.ciphertext_sub(
+                    &key.encrypt_with_nonce(&Uint::ZERO, &z_i),
+                    &key.ciphertext_mul_scalar(&u_i, &e_i),
+                ));
+                e.push(e_i);
+                z.push(z_i);
+            }
+        }
+
+        let challenge = challenge(key, context, set, c, &a);
+        let others = e.iter().fold(Uint::ZERO, |acc, e_i| acc.wrapping_add(e_i));
+        e[index] = truncate(&challenge.wrapping_sub(&others));
+        z[index] = key.nonce_add(&s, &key.nonce_mul_scalar(r, &e[index]));
+
+        MembershipProof { a, e, z }
+    }
+
+    pub fn prove_bit<R: CryptoRng + ?Sized>(
+        key: &PublicKey<S, D>,
+        context: &[u8],
+        bit: bool,
+        encryption: &(NonZero<Uint<D>>, NonZero<Uint<S>>),
+        rng: &mut R,
+    ) -> Self {
+        Self::prove(key, context, &[Uint::ZERO, Uint::ONE], bit as usize, encryption, rng)
+    }
+
+    pub fn verify(&self, key: &PublicKey<S, D>, context: &[u8], set: &[Uint<S>], c: &NonZero<Uint<D>>) -> bool {
+        if !self.is_well_formed(key, context, set, c) {
+            return false;
+        }
+
+        self.a
+            .iter()
+            .zip(&self.e)
+            .zip(&self.z)
+            .zip(set)
+            .all(|(((a, e), z), m)| {
+                let u = key.ciphertext_sub_plain(c, m);
+                let lhs = key.encrypt_with_nonce(&Uint::ZERO, z);
+                let rhs = key.ciphertext_add(a, &key.ciphertext_mul_scalar(&u, e));
+                lhs.ct_eq(&rhs).into()
+            })
+    }
+
+    pub fn verify_bit(&self, key: &PublicKey<S, D>, context: &[u8], c: &NonZero<Uint<D>>) -> bool {
+        self.verify(key, context, &[Uint::ZERO, Uint::ONE], c)
+    }
+
+    // all branch equations z^n = a * u^e are folded with random weights lambda into a single nth power,
+    // (prod z^lambda)^n = prod a^lambda * u^(lambda * e), so a batch costs one exponentiation by n
+    pub fn verify_batch<R: CryptoRng + ?Sized>(
+        key: &PublicKey<S, D>,
+        set: &[Uint<S>],
+        contexts: &[&[u8]],
+        ciphertexts: &[NonZero<Uint<D>>],
+        proofs: &[MembershipProof<S, D>],
+        rng: &mut R,
+    ) -> bool {
+        if ciphertexts.len() != proofs.len() || contexts.len() != proofs.len() {
+            return false;
+        }
+
+        let mut z_acc = NonZero::<Uint<S>>::ONE;
+        let mut rhs_acc = NonZero::<Uint<D>>::ONE;
+        for ((c, proof), context) in ciphertexts.iter().zip(proofs).zip(contexts) {
+            if !proof.is_well_formed(key, context, set, c) {
+                return false;
+            }
+
+            for (((a, e), z), m) in proof.a.iter().zip(&proof.e).zip(&proof.z).zip(set) {
+                let lambda = Uint::<S>::random_bits(rng, BATCH_WEIGHT_BITS);
+                let u = key.ciphertext_sub_plain(c, m);
+                z_acc = key.nonce_add(&z_acc, &key.nonce_mul_scalar(z, &lambda));
+                rhs_acc = key.ciphertext_add(&rhs_acc, &key.ciphertext_mul_scalar(a, &lambda));
+                rhs_acc = key.ciphertext_add(&rhs_acc, &key.ciphertext_mul_scalar(&u, &lambda.wrapping_mul(e)));
+            }
+        }
+
+        key.encrypt_with_nonce(&Uint::ZERO, &z_acc).ct_eq(&rhs_acc).into()
+    }
+
+    // branch i is encoded as a_i | e_i | z_i at the fixed widths of the key, so a proof over k values takes
+    // `encoded_len(k)` bytes
+    pub(crate) fn to_bytes(&self, key: &PublicKey<S, D>) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::encoded_len(self.a.len()));
+        for ((a, e), z) in self.a.iter().zip(&self.e).zip(&self.z) {
+            bytes.extend_from_slice(&key.ciphertext_to_bytes(a));
+            bytes.extend_from_slice(&uint_to_be_bytes(e));
+            bytes.extend_from_slice(&key.nonce_to_bytes(z));
+        }
+
+        bytes
+    }
+
+    pub(crate) fn from_bytes(key: &PublicKey<S, D>, bytes: &[u8]) -> Option<Self> {
+        let branch_len = Self::encoded_len(1);
+        if bytes.is_empty() || bytes.len() % branch_len != 0 {
+            return None;
+        }
+
+        let branches = bytes.len() / branch_len;
+        let mut a = Vec::with_capacity(branches);
+        let mut e = Vec::with_capacity(branches);
+        let mut z = Vec::with_capacity(branches);
+        for branch in bytes.chunks_exact(branch_len) {
+            let (a_i, rest) = branch.split_at(Uint::<D>::BYTES);
+            let (e_i, z_i) = rest.split_at(Uint::<S>::BYTES);
+            a.push(Option::from(key.ciphertext_from_bytes(a_i))?);
+            e.push(Uint::from_be_slice(e_i));
+            z.push(Option::from(key.nonce_from_bytes(z_i))?);
+        }
+
+        Some(MembershipProof { a, e, z })
+    }
+
+    pub(crate) fn encoded_len(branches: usize) -> usize {
+        branches * (Uint::<D>::BYTES + 2 * Uint::<S>::BYTES)
+    }
+
+    fn is_well_formed(&self, key: &PublicKey<S, D>, context: &[u8], set: &[Uint<S>], c: &NonZero<Uint<D>>) -> bool {
+        if set.is_empty() || self.a.len() != set.len() || self.e.len() != set.len() || self.z.len() != set.len() {
+            return false;
+        }
+
+        let bound = Uint::ONE.shl_vartime(CHALLENGE_BITS);
+        let is_valid = key.ciphertext_is_valid(c)
+            & set.iter().fold(1.into(), |acc, m| acc & key.plaintext_is_valid(m))
+            & self.a.iter().fold(1.into(), |acc, a| acc & key.ciphertext_is_valid(a))
+            & self.e.iter().fold(1.into(), |acc, e| acc & e.ct_lt(&bound))
+            & self.z.iter().fold(1.into(), |acc, z| acc & key.nonce_is_valid(z));
+        if !bool::from(is_valid) {
+            return false;
+        }
+
+        let sum = self.e.iter().fold(Uint::ZERO, |acc, e_i| acc.wrapping_add(e_i));
+        truncate(&sum) == challenge(key, context, set, c, &self.a)
+    }
+}
+
+fn truncate<const S: usize>(x: &Uint<S>) -> Uint<S> {
+    x.bitand(&Uint::MAX.shr_vartime(Uint::<S>::BITS - CHALLENGE_BITS))
+}
+
+fn challenge<const S: usize, const D: usize>(
+    key: &PublicKey<S, D>,
+    context: &[u8],
+    set: &[Uint<S>],
+    c: &NonZero<Uint<D>>,
+    a: &[NonZero<Uint<D>>],
+) -> Uint<S> {
+    let mut transcript = Transcript::new(b"paillier-membership");
+    transcript.append_uint(b"n", key.n.as_ref());
+    transcript.append_bytes(b"context", context);
+    for m in set {
+        transcript.append_uint(b"m", m);
+    }
+    transcript.append_uint(b"c", c.as_ref());
+    for a_i in a {
+        transcript.append_uint(b"a", a_i.as_ref());
+    }
+    transcript.challenge_bits(b"e", CHALLENGE_BITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sk::SecretKey;
+    use crate::zk::MembershipProof;
+    use crate::{EncryptionKey, HomomorphicKey, KeyGenerator};
+    use crypto_bigint::{U256, U512, U1024};
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    type SmallSecretKey = SecretKey<{ U256::LIMBS }, { U512::LIMBS }, { U1024::LIMBS }>;
+
+    #[test]
+    fn should_prove_and_verify_bits() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (_, pk) = SmallSecretKey::random(&mut rng);
+
+        for bit in [false, true] {
+            let encryption = pk.encrypt(&U512::from(bit as u8), &mut rng);
+            let proof = MembershipProof::prove_bit(&pk, b"test", bit, &encryption, &mut rng);
+            assert!(proof.verify_bit(&pk, b"test", &encryption.0));
+        }
+
+        // claiming 1 for an encryption of 2
+        let encryption = pk.encrypt(&U512::from(2u8), &mut rng);
+        let proof = MembershipProof::prove_bit(&pk, b"test", true, &encryption, &mut rng);
+        assert!(!proof.verify_bit(&pk, b"test", &encryption.0));
+    }
+
+    #[test]
+    fn should_prove_membership_of_aggregated_ciphertext() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (_, pk) = SmallSecretKey::random(&mut rng);
+        let set = [U512::ZERO, U512::ONE, U512::from(2u8), U512::from(3u8)];
+
+        let (c1, r1) = pk.encrypt(&U512::ONE, &mut rng);
+        let (c2, r2) = pk.encrypt(&U512::from(2u8), &mut rng);
+        let aggregate = (pk.ciphertext_add(&c1, &c2), pk.nonce_add(&r1, &r2));
+
+        let proof = MembershipProof::prove(&pk, b"test", &set, 3, &aggregate, &mut rng);
+        assert!(proof.verify(&pk, b"test", &set, &aggregate.0));
+        assert!(!proof.verify(&pk, b"test", &set[..3], &aggregate.0));
+        assert!(!proof.verify(&pk, b"test", &set, &c1));
+    }
+
+    #[test]
+    fn should_verify_batch() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let (_, pk) = SmallSecretKey::random(&mut rng);
+        let set = [U512::ZERO, U512::ONE];
+
+        let voters: Vec<[u8; 1]> = (0..8).map(|i| [i]).collect();
+        let contexts: Vec<&[u8]> = voters.iter().map(|voter| voter.as_slice()).collect();
+        let mut ciphertexts = Vec::new();
+        let mut proofs = Vec::new();
+        for i in 0..8 {
+            let encryption = pk.encrypt(&set[i % 2], &mut rng);
+            proofs.push(MembershipProof::prove(
+                &pk,
+                contexts[i],
+                &set,
+                i % 2,
+                &encryption,
+                &mut rng,
+            ));
+            ciphertexts.push(encryption.0);
+        }
+        assert!(MembershipProof::verify_batch(
+            &pk,
+            &set,
+            &contexts,
+            &ciphertexts,
+            &proofs,
+            &mut rng
+        ));
+
+        // proofs are bound to their own context
+        let mut swapped = contexts.clone();
+        swapped.swap(0, 1);
+        assert!(!MembershipProof::verify_batch(
+            &pk,
+            &set,
+            &swapped,
+            &ciphertexts,
+            &proofs,
+            &mut rng
+        ));
+
+        // a single forged ballot spoils the batch
+        let forged = pk.encrypt(&U512::from(5u8), &mut rng);
+        proofs[3] = MembershipProof::prove(&pk, contexts[3], &set, 1, &forged, &mut rng);
+        ciphertexts[3] = forged.0;
+        assert!(!MembershipProof::verify_batch(
+            &pk,
+            &set,
+            &contexts,
+            &ciphertexts,
+            &proofs,
+            &mut rng
+        ));
+        assert!(!MembershipProof::verify_batch(
+            &pk,
+            &set,
+            &contexts,
+            &ciphertexts[..7],
+            &proofs,
+            &mut rng
+        ));
+    }
+}
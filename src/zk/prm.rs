@@ -0,0 +1,103 @@
+use crate::ring_pedersen::{RingPedersenParams, RingPedersenSecret};
+use crate::zk::Transcript;
+use crypto_bigint::modular::{MontyForm, SafeGcdInverter};
+use crypto_bigint::{Odd, PrecomputeInverter, RandomMod, Uint};
+use rand_core::CryptoRng;
+use subtle::{ConditionallySelectable, ConstantTimeEq, ConstantTimeLess};
+
+// ring-Pedersen parameters proof (CGGMP21, figure 17), each round has soundness error 1/2
+const ROUNDS: usize = 128;
+
+#[derive(Debug, Clone)]
+pub struct RingPedersenProof<const S: usize> {
+    a: Vec<Uint<S>>,
+    z: Vec<Uint<S>>,
+}
+
+impl<const S: usize, const S_UNSAT: usize> RingPedersenProof<S>
+where
+    Odd<Uint<S>>: PrecomputeInverter<Inverter = SafeGcdInverter<S, S_UNSAT>>,
+{
+    pub fn prove<R: CryptoRng + ?Sized>(secret: &RingPedersenSecret<S>, rng: &mut R) -> Self {
+        let params = &secret.params;
+        let t = MontyForm::new(&params.t, params.monty_params);
+
+        let nonces: Vec<Uint<S>> = (0..ROUNDS).map(|_| Uint::random_mod(rng, &secret.phi)).collect();
+        let a = nonces.iter().map(|alpha| t.pow(alpha).retrieve()).collect();
+        let mut proof = RingPedersenProof { a, z: Vec::new() };
+
+        let e = proof.challenge(params);
+        proof.z = nonces
+            .iter()
+            .enumerate()
+            .map(|(i, alpha)| {
+                let lambda = Uint::conditional_select(&Uint::ZERO, &secret.lambda, e.bit(i as u32).into());
+                alpha.add_mod(&lambda, &secret.phi)
+            })
+            .collect();
+
+        proof
+    }
+
+    pub fn verify(&self, params: &RingPedersenParams<S>) -> bool {
+        if self.a.len() != ROUNDS || self.z.len() != ROUNDS {
+            return false;
+        }
+
+        let n = params.n.as_ref();
+        let is_well_formed = params.s.ct_lt(n)
+            & params.t.ct_lt(n)
+            & params.s.gcd(n).ct_eq(&Uint::ONE)
+            & params.t.gcd(n).ct_eq(&Uint::ONE)
+            & params.t.ct_ne(&Uint::ONE);
+        if !bool::from(is_well_formed) || self.a.iter().chain(&self.z).any(|x| !bool::from(x.ct_lt(n))) {
+            return false;
+        }
+
+        let e = self.challenge(params);
+        let t = MontyForm::new(&params.t, params.monty_params);
+        let s = MontyForm::new(&params.s, params.monty_params);
+        self.a.iter().zip(&self.z).enumerate().all(|(i, (a, z))| {
+            let a = MontyForm::new(a, params.monty_params);
+            let rhs = if e.bit_vartime(i as u32) { a * s } else { a };
+            t.pow(z).ct_eq(&rhs).into()
+        })
+    }
+
+    fn challenge(&self, params: &RingPedersenParams<S>) -> Uint<S> {
+        let mut transcript = Transcript::new(b"ring-pedersen-parameters");
+        params.append_to(&mut transcript);
+        for a in &self.a {
+            transcript.append_uint(b"a", a);
+        }
+        transcript.challenge_uint(b"e")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ring_pedersen::{RingPedersenParams, RingPedersenSecret};
+    use crate::zk::RingPedersenProof;
+    use crypto_bigint::U512;
+    use rand_chacha::ChaCha8Rng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    #[test]
+    fn should_prove_and_verify_parameters() {
+        let mut rng = ChaCha8Rng::from_os_rng();
+        let secret = RingPedersenSecret::<{ U512::LIMBS }>::random(&mut rng);
+        let params = secret.params();
+
+        let proof = RingPedersenProof::prove(&secret, &mut rng);
+        assert!(proof.verify(params));
+
+        // s outside of the group generated by t
+        let other = RingPedersenSecret::<{ U512::LIMBS }>::random(&mut rng);
+        let forged = RingPedersenParams::from_parts_unchecked(params.n, *other.params().s(), params.t);
+        assert!(!proof.verify(&forged));
+
+        let mut truncated = proof.clone();
+        truncated.z.pop();
+        assert!(!truncated.verify(params));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::utils::uint_to_be_bytes;
+#[cfg(feature = "curve")]
+use crypto_bigint::NonZero;
+use crypto_bigint::Uint;
+use sha2::{Digest, Sha256};
+
+// Fiat-Shamir transcript, every item is length prefixed so that distinct transcripts never hash alike
+#[derive(Clone)]
+pub(crate) struct Transcript {
+    hasher: Sha256,
+}
+
+impl Transcript {
+    pub(crate) fn new(domain: &'static [u8]) -> Self {
+        let mut transcript = Transcript { hasher: Sha256::new() };
+        transcript.append_bytes(b"domain", domain);
+        transcript
+    }
+
+    pub(crate) fn append_bytes(&mut self, label: &'static [u8], bytes: &[u8]) {
+        self.hasher.update((label.len() as u64).to_be_bytes());
+        self.hasher.update(label);
+        self.hasher.update((bytes.len() as u64).to_be_bytes());
+        self.hasher.update(bytes);
+    }
+
+    pub(crate) fn append_uint<const L: usize>(&mut self, label: &'static [u8], x: &Uint<L>) {
+        self.append_bytes(label, &uint_to_be_bytes(x));
+    }
+
+    pub(crate) fn challenge_uint<const L: usize>(&self, label: &'static [u8]) -> Uint<L> {
+        // expand the transcript hash in counter mode to the full width of the integer
+        let mut seed = self.clone();
+        seed.append_bytes(b"challenge", label);
+        let seed = seed.hasher.finalize();
+
+        let mut bytes = Vec::with_capacity(Uint::<L>::BYTES + 32);
+        let mut counter = 0u32;
+        while bytes.len() < Uint::<L>::BYTES {
+            bytes.extend_from_slice(
+                &Sha256::new()
+                    .chain_update(seed)
+                    .chain_update(counter.to_be_bytes())
+                    .finalize(),
+            );
+            counter += 1;
+        }
+        bytes.truncate(Uint::<L>::BYTES);
+
+        Uint::from_be_slice(&bytes)
+    }
+
+    #[cfg(feature = "curve")]
+    pub(crate) fn challenge_mod<const L: usize>(&self, label: &'static [u8], modulus: &NonZero<Uint<L>>) -> Uint<L> {
+        // the bias is negligible as long as the modulus is much shorter than the integer width
+        self.challenge_uint::<L>(label).rem(modulus)
+    }
+
+    pub(crate) fn challenge_bits<const L: usize>(&self, label: &'static [u8], bits: u32) -> Uint<L> {
+        self.challenge_uint::<L>(label).shr_vartime(Uint::<L>::BITS - bits)
+    }
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "cli")]
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn paillier(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_paillier"))
+        .args(args)
+        .output()
+        .expect("binary runs")
+}
+
+fn write_temp(name: &str, contents: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("paillier-cli-{}-{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap().trim_end().to_owned()
+}
+
+#[test]
+fn should_encrypt_decrypt_and_report_exit_codes() {
+    let keygen = paillier(&["keygen", "--format", "json"]);
+    assert!(keygen.status.success());
+    let key = write_temp("key.json", &keygen.stdout);
+    let key = key.to_str().unwrap();
+
+    let encrypt = paillier(&["encrypt", "--key", key, "42"]);
+    assert!(encrypt.status.success());
+    let decrypt = paillier(&["decrypt", "--key", key, &stdout(&encrypt)]);
+    assert!(decrypt.status.success());
+    assert_eq!(stdout(&decrypt), "42");
+
+    let missing = std::env::temp_dir().join(format!("paillier-cli-{}-missing", std::process::id()));
+    let output = paillier(&["encrypt", "--key", missing.to_str().unwrap(), "42"]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let garbage = write_temp("garbage.pem", b"not a key");
+    let output = paillier(&["encrypt", "--key", garbage.to_str().unwrap(), "42"]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let output = paillier(&["decrypt", "--key", key, "zz"]);
+    assert_eq!(output.status.code(), Some(4));
+
+    let output = paillier(&["encrypt", "--key", key, "forty-two"]);
+    assert_eq!(output.status.code(), Some(5));
+
+    std::fs::remove_file(key).unwrap();
+    std::fs::remove_file(garbage).unwrap();
+}
+
+#[test]
+fn should_reject_3072_bit_keys() {
+    let output = paillier(&["keygen", "--bits", "3072"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("3072 bit keys are not supported"));
+
+    let help = paillier(&["keygen", "--help"]);
+    assert!(String::from_utf8_lossy(&help.stdout).contains("3072 bit keys are not supported"));
+}